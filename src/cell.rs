@@ -1,9 +1,21 @@
-use std::collections::BTreeSet;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Whether a shift is only scheduled or was actually worked.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum Status {
+    #[default]
+    Planned,
+    Confirmed,
+}
+
+/// Config names assigned to one day.
+pub type Assigned = BTreeMap<String, Status>;
 
 #[derive(Default)]
 pub struct Cell {
     pub selected: bool,
-    pub config_names: BTreeSet<String>,
+    pub config_names: Assigned,
 }
 
 impl Cell {
@@ -13,7 +25,11 @@ impl Cell {
     }
 
     pub fn contains(&self, name: &str) -> bool {
-        self.config_names.contains(name)
+        self.config_names.contains_key(name)
+    }
+
+    pub fn is_confirmed(&self, name: &str) -> bool {
+        self.config_names.get(name) == Some(&Status::Confirmed)
     }
 
     pub fn deselect(&mut self) {
@@ -28,11 +44,27 @@ impl Cell {
         self.config_names.clear();
     }
 
+    /// Keeps the status if the name is already there.
     pub fn insert(&mut self, name: String) {
-        self.config_names.insert(name);
+        self.config_names.entry(name).or_default();
     }
 
     pub fn remove(&mut self, name: &str) {
         self.config_names.remove(name);
     }
+
+    pub fn toggle_status(&mut self, name: &str) {
+        if let Some(status) = self.config_names.get_mut(name) {
+            *status = match status {
+                Status::Planned => Status::Confirmed,
+                Status::Confirmed => Status::Planned,
+            };
+        }
+    }
+
+    pub fn confirm_all(&mut self) {
+        self.config_names
+            .values_mut()
+            .for_each(|x| *x = Status::Confirmed);
+    }
 }
@@ -1,34 +1,54 @@
-use crate::{failure::Failure, util};
+use crate::{failure::Failure, tax::Totals, util};
 use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct HourMinute {
     hour: u8,
     minute: u8,
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum Type {
     PerTime,
     PerHour(HourMinute),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum TypeForPickList {
     PerTime,
     PerHour,
 }
 
-#[derive(Serialize, Deserialize)]
+/// Whether `pay` already contains consumption tax.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum TaxBasis {
+    #[default]
+    Inclusive,
+    Exclusive,
+}
+
+/// The default, inclusive at 0%, leaves pay as it is.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct Tax {
+    pub basis: TaxBasis,
+    /// In percent.
+    pub rate: u8,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct Config {
     pub r#type: Type,
     pub pay: u32,
+    #[serde(default)]
+    pub tax: Tax,
 }
 
 pub const TYPES_FOR_PICK_LIST: [TypeForPickList; 2] =
     [TypeForPickList::PerTime, TypeForPickList::PerHour];
 
+pub const TAX_BASES: [TaxBasis; 2] = [TaxBasis::Inclusive, TaxBasis::Exclusive];
+
 impl Type {
     pub fn duration_to_string(&self) -> String {
         match self {
@@ -77,6 +97,24 @@ impl Display for TypeForPickList {
     }
 }
 
+impl Display for TaxBasis {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Inclusive => write!(f, "Incl."),
+            Self::Exclusive => write!(f, "Excl."),
+        }
+    }
+}
+
+impl Display for Tax {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.basis {
+            TaxBasis::Inclusive => write!(f, "{}% tax included", self.rate),
+            TaxBasis::Exclusive => write!(f, "{}% tax excluded", self.rate),
+        }
+    }
+}
+
 impl Config {
     pub fn pay_to_string(&self) -> String {
         format!(
@@ -86,6 +124,13 @@ impl Config {
         )
     }
 
+    /// Pre-tax, tax and tax-included amounts of `count` shifts on their own.
+    pub fn totals(&self, count: usize) -> Totals {
+        Totals::new([(self.sum(count), self.tax)])
+    }
+
+    /// At face value: tax is neither added nor taken out here, since it has
+    /// to be rounded once over all lines (see [`Totals`]).
     pub fn sum(&self, count: usize) -> u32 {
         match self.r#type {
             Type::PerTime => self.pay * count as u32,
@@ -0,0 +1,428 @@
+use crate::failure::{Cause, Failure};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Something printable that is written out as HTML, and as PDF too when
+/// built with the `pdf` feature.
+pub trait Document {
+    /// File name without extension.
+    fn stem(&self) -> String;
+    fn to_html(&self) -> String;
+    /// Fails when no installed font can show what the user typed.
+    #[cfg(feature = "pdf")]
+    fn to_pdf(&self) -> Result<Vec<u8>, Failure>;
+    /// What the user typed that goes into the PDF, for picking its fonts.
+    #[cfg(feature = "pdf")]
+    fn pdf_texts(&self) -> Vec<&str>;
+}
+
+pub fn escape(text: &str) -> String {
+    let mut retval = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => retval.push_str("&amp;"),
+            '<' => retval.push_str("&lt;"),
+            '>' => retval.push_str("&gt;"),
+            '"' => retval.push_str("&quot;"),
+            '\'' => retval.push_str("&#39;"),
+            c => retval.push(c),
+        }
+    }
+
+    retval
+}
+
+/// Where [`write`] puts `document`.
+pub fn paths(document: &impl Document, dir: &Path) -> Vec<PathBuf> {
+    let stem = document.stem();
+
+    EXTENSIONS
+        .iter()
+        .map(|extension| dir.join(format!("{stem}.{extension}")))
+        .collect()
+}
+
+#[cfg(feature = "pdf")]
+const EXTENSIONS: [&str; 2] = ["html", "pdf"];
+#[cfg(not(feature = "pdf"))]
+const EXTENSIONS: [&str; 1] = ["html"];
+
+/// Returns the paths written, HTML first, and why the PDF was left out if it
+/// was. The HTML alone is still complete.
+pub fn write(
+    document: &impl Document,
+    dir: &Path,
+    failure: fn(PathBuf, Cause) -> Failure,
+) -> Result<(Vec<PathBuf>, Option<Failure>), Failure> {
+    let stem = document.stem();
+    let write = |extension: &str, contents: &[u8]| -> Result<PathBuf, Failure> {
+        let path = dir.join(format!("{stem}.{extension}"));
+
+        fs::create_dir_all(dir)
+            .and_then(|()| fs::write(&path, contents))
+            .map_err(|e| failure(path.clone(), Cause::Io(e)))?;
+
+        Ok(path)
+    };
+
+    let html = write("html", document.to_html().as_bytes())?;
+
+    #[cfg(feature = "pdf")]
+    match document.to_pdf() {
+        Ok(pdf) => {
+            let pdf = write("pdf", &pdf)?;
+            Ok((vec![html, pdf], None))
+        }
+        Err(failure) => Ok((vec![html], Some(failure))),
+    }
+
+    #[cfg(not(feature = "pdf"))]
+    Ok((vec![html], None))
+}
+
+#[cfg(feature = "pdf")]
+pub mod pdf {
+    use crate::{failure::Failure, fonts};
+    use iced::Color;
+    use pdf_writer::{
+        Content, Filter, Finish, Name, Pdf, Rect, Ref, Str,
+        types::{CidFontType, FontFlags, SystemInfo, UnicodeCmap},
+    };
+    use std::collections::BTreeMap;
+
+    pub const SANS: Name = Name(b"F1");
+    pub const MONO: Name = Name(b"F2");
+    /// [`Embedded`], standing in for either.
+    const EMBEDDED: Name = Name(b"F3");
+
+    /// Latin-1 matches WinAnsi in these ranges, `¥` included.
+    fn is_win_ansi(c: char) -> bool {
+        matches!(c, ' '..='~' | '\u{A0}'..='\u{FF}')
+    }
+
+    /// Anything outside WinAnsi comes out as `?`.
+    fn win_ansi(text: &str) -> Vec<u8> {
+        text.chars()
+            .map(|c| if is_win_ansi(c) { c as u8 } else { b'?' })
+            .collect()
+    }
+
+    fn face(data: &[u8], index: u32) -> Option<ttf_parser::Face<'_>> {
+        ttf_parser::Face::parse(data, index).ok()
+    }
+
+    /// One of [`fonts::fallbacks`], for the Japanese names and such that the
+    /// standard fonts cannot show. Only the glyphs drawn are embedded.
+    struct Embedded {
+        data: Vec<u8>,
+        index: u32,
+        /// Drawn so far, by glyph ID.
+        glyphs: BTreeMap<u16, char>,
+    }
+
+    impl Embedded {
+        /// The first face that has every character of `texts` outside WinAnsi,
+        /// or `None` if there are no such characters.
+        fn find<'a>(texts: impl IntoIterator<Item = &'a str>) -> Result<Option<Self>, Failure> {
+            let mut needed: Vec<char> = texts
+                .into_iter()
+                .flat_map(str::chars)
+                // line breaks only separate lines
+                .filter(|&c| !is_win_ansi(c) && !c.is_control())
+                .collect();
+            needed.sort();
+            needed.dedup();
+
+            let Some(&first) = needed.first() else {
+                return Ok(None);
+            };
+
+            let database = fonts::system();
+            // the character missing from the best face, to explain why none fits
+            let mut missing = None;
+
+            for id in fonts::fallbacks(&database) {
+                let found = database.with_face_data(id, |data, index| {
+                    let face = face(data, index)?;
+
+                    match needed.iter().find(|&&c| face.glyph_index(c).is_none()) {
+                        Some(&c) => {
+                            missing.get_or_insert(c);
+                            None
+                        }
+                        None => Some((data.to_vec(), index)),
+                    }
+                });
+
+                if let Some(Some((data, index))) = found {
+                    return Ok(Some(Self {
+                        data,
+                        index,
+                        glyphs: BTreeMap::new(),
+                    }));
+                }
+            }
+
+            Err(Failure::PdfCharacter(missing.unwrap_or(first)))
+        }
+
+        fn face(&self) -> ttf_parser::Face<'_> {
+            face(&self.data, self.index).expect("parsed when found")
+        }
+
+        /// CIDs are glyph IDs, except in CID-keyed CFF outlines.
+        fn cid(face: &ttf_parser::Face, glyph: u16) -> u16 {
+            face.tables()
+                .cff
+                .and_then(|cff| cff.glyph_cid(ttf_parser::GlyphId(glyph)))
+                .unwrap_or(glyph)
+        }
+
+        /// Two bytes per CID, as `Identity-H` takes them.
+        fn encode(&mut self, text: &str) -> Vec<u8> {
+            let face = face(&self.data, self.index).expect("parsed when found");
+            let mut retval = Vec::new();
+
+            for c in text.chars() {
+                let glyph = face.glyph_index(c).map_or(0, |x| x.0);
+                self.glyphs.insert(glyph, c);
+                retval.extend(Self::cid(&face, glyph).to_be_bytes());
+            }
+
+            retval
+        }
+
+        fn write(
+            &self,
+            pdf: &mut Pdf,
+            [type0_id, cid_id, descriptor_id, file_id, unicode_id]: [Ref; 5],
+        ) -> Result<(), Failure> {
+            let glyphs: Vec<u16> = self.glyphs.keys().copied().collect();
+            // keeps glyph IDs, so the widths and CIDs below still hold
+            let subset =
+                subsetter::subset(&self.data, self.index, subsetter::Profile::pdf(&glyphs))
+                    .map_err(|_| Failure::PdfFont)?;
+            let face = self.face();
+            let em = |x: f32| x * 1000.0 / face.units_per_em() as f32;
+            let cff = face.tables().cff.is_some();
+            let name = face
+                .names()
+                .into_iter()
+                .find(|x| x.name_id == ttf_parser::name_id::POST_SCRIPT_NAME)
+                .and_then(|x| x.to_string())
+                .unwrap_or_else(|| "Fallback".to_string());
+            // marks a subset
+            let name = format!("CALCJP+{name}");
+            let name = Name(name.as_bytes());
+            let system_info = SystemInfo {
+                registry: Str(b"Adobe"),
+                ordering: Str(b"Identity"),
+                supplement: 0,
+            };
+
+            pdf.type0_font(type0_id)
+                .base_font(name)
+                .encoding_predefined(Name(b"Identity-H"))
+                .descendant_font(cid_id)
+                .to_unicode(unicode_id);
+
+            let mut cid_font = pdf.cid_font(cid_id);
+            cid_font
+                .subtype(if cff {
+                    CidFontType::Type0
+                } else {
+                    CidFontType::Type2
+                })
+                .base_font(name)
+                .system_info(system_info)
+                .font_descriptor(descriptor_id)
+                .default_width(0.0);
+
+            if !cff {
+                cid_font.cid_to_gid_map_predefined(Name(b"Identity"));
+            }
+
+            let mut widths = cid_font.widths();
+
+            for &glyph in self.glyphs.keys() {
+                let advance = face
+                    .glyph_hor_advance(ttf_parser::GlyphId(glyph))
+                    .unwrap_or(0);
+                widths.consecutive(Self::cid(&face, glyph), [em(advance as f32)]);
+            }
+
+            widths.finish();
+            cid_font.finish();
+
+            let bbox = face.global_bounding_box();
+            let mut descriptor = pdf.font_descriptor(descriptor_id);
+            descriptor
+                .name(name)
+                .flags(FontFlags::SYMBOLIC)
+                .bbox(Rect::new(
+                    em(bbox.x_min as f32),
+                    em(bbox.y_min as f32),
+                    em(bbox.x_max as f32),
+                    em(bbox.y_max as f32),
+                ))
+                .italic_angle(0.0)
+                .ascent(em(face.ascender() as f32))
+                .descent(em(face.descender() as f32))
+                .cap_height(em(face.capital_height().unwrap_or(face.ascender()) as f32))
+                .stem_v(80.0);
+
+            if cff {
+                descriptor.font_file3(file_id);
+            } else {
+                descriptor.font_file2(file_id);
+            }
+
+            descriptor.finish();
+
+            let compressed = miniz_oxide::deflate::compress_to_vec_zlib(&subset, 6);
+            let mut file = pdf.stream(file_id, &compressed);
+            file.filter(Filter::FlateDecode);
+
+            if cff {
+                file.pair(Name(b"Subtype"), Name(b"OpenType"));
+            }
+
+            file.finish();
+
+            let mut unicode = UnicodeCmap::new(Name(b"Calc-UCS"), system_info);
+
+            for (&glyph, &c) in &self.glyphs {
+                unicode.pair(Self::cid(&face, glyph), c);
+            }
+
+            pdf.cmap(unicode_id, &unicode.finish())
+                .name(Name(b"Calc-UCS"))
+                .system_info(system_info);
+
+            Ok(())
+        }
+    }
+
+    /// A document of same-sized pages, drawn one after another, with [`SANS`]
+    /// and [`MONO`] available.
+    pub struct Pages {
+        width: f32,
+        height: f32,
+        done: Vec<Content>,
+        content: Content,
+        embedded: Option<Embedded>,
+    }
+
+    impl Pages {
+        /// `texts` are what the user typed, checked against the fonts.
+        pub fn new<'a>(
+            width: f32,
+            height: f32,
+            texts: impl IntoIterator<Item = &'a str>,
+        ) -> Result<Self, Failure> {
+            Ok(Self {
+                width,
+                height,
+                done: Vec::new(),
+                content: Content::new(),
+                embedded: Embedded::find(texts)?,
+            })
+        }
+
+        /// Of the page being drawn.
+        pub fn content(&mut self) -> &mut Content {
+            &mut self.content
+        }
+
+        /// Starts drawing on a fresh page, with colors and line widths reset.
+        pub fn next_page(&mut self) {
+            self.done
+                .push(std::mem::replace(&mut self.content, Content::new()));
+        }
+
+        pub fn fill(&mut self, color: Color) -> &mut Self {
+            self.content.set_fill_rgb(color.r, color.g, color.b);
+            self
+        }
+
+        /// Text outside WinAnsi is drawn in the embedded font instead of `font`.
+        pub fn text(&mut self, font: Name, size: f32, x: f32, y: f32, text: &str) -> &mut Self {
+            let (font, encoded) = match &mut self.embedded {
+                Some(embedded) if !text.chars().all(is_win_ansi) => {
+                    (EMBEDDED, embedded.encode(text))
+                }
+                _ => (font, win_ansi(text)),
+            };
+
+            self.content
+                .begin_text()
+                .set_font(font, size)
+                .next_line(x, y)
+                .show(Str(&encoded))
+                .end_text();
+            self
+        }
+
+        /// Courier is 0.6 em wide per glyph, which makes right alignment easy.
+        pub fn mono_right(&mut self, size: f32, right: f32, y: f32, text: &str) -> &mut Self {
+            let width = text.chars().count() as f32 * 0.6 * size;
+            self.text(MONO, size, right - width, y, text)
+        }
+
+        pub fn finish(mut self) -> Result<Vec<u8>, Failure> {
+            self.next_page();
+
+            let catalog_id = Ref::new(1);
+            let page_tree_id = Ref::new(2);
+            let sans_id = Ref::new(3);
+            let mono_id = Ref::new(4);
+            let embedded_ids = [5, 6, 7, 8, 9].map(Ref::new);
+            // a page and its content per page from here on
+            let page_ids: Vec<_> = (0..self.done.len() as i32)
+                .map(|i| (Ref::new(10 + 2 * i), Ref::new(11 + 2 * i)))
+                .collect();
+
+            let mut pdf = Pdf::new();
+            pdf.catalog(catalog_id).pages(page_tree_id);
+            pdf.pages(page_tree_id)
+                .kids(page_ids.iter().map(|&(page_id, _)| page_id))
+                .count(page_ids.len() as i32);
+
+            for (&(page_id, content_id), content) in page_ids.iter().zip(self.done) {
+                let mut page = pdf.page(page_id);
+                page.media_box(Rect::new(0.0, 0.0, self.width, self.height));
+                page.parent(page_tree_id);
+                page.contents(content_id);
+
+                let mut resources = page.resources();
+                let mut fonts = resources.fonts();
+                fonts.pair(SANS, sans_id).pair(MONO, mono_id);
+
+                if self.embedded.is_some() {
+                    fonts.pair(EMBEDDED, embedded_ids[0]);
+                }
+
+                drop(fonts);
+                drop(resources);
+                drop(page);
+
+                pdf.stream(content_id, &content.finish());
+            }
+
+            for (id, font) in [(sans_id, b"Helvetica" as &[u8]), (mono_id, b"Courier")] {
+                pdf.type1_font(id)
+                    .base_font(Name(font))
+                    .encoding_predefined(Name(b"WinAnsiEncoding"));
+            }
+
+            if let Some(embedded) = &self.embedded {
+                embedded.write(&mut pdf, embedded_ids)?;
+            }
+
+            Ok(pdf.finish())
+        }
+    }
+}
@@ -1,45 +1,206 @@
 use std::{
     error::Error,
     fmt::{Display, Formatter},
+    io,
+    path::PathBuf,
 };
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug)]
 pub enum Failure {
     Cell(usize),
-    Offset,
-    Year,
-    Load,
-    Save,
+    NoFileSelected,
+    #[cfg(feature = "sqlite")]
+    NoSuchSave(String),
+    Load(PathBuf, Cause),
+    Save(PathBuf, Cause),
     SaveEmptyName,
+    Filename,
     Pay,
-    FileRemove,
+    FileRemove(PathBuf, Cause),
     DurationParse,
     Duration,
     Date,
+    Settings(PathBuf, Cause),
+    Autosave(PathBuf, Cause),
+    Backup(PathBuf, Cause),
+    BackupCount,
+    UtcOffset,
+    Goal,
+    Payslip,
+    Report(PathBuf, Cause),
+    Invoice(PathBuf, Cause),
+    InvoiceNames,
+    InvoiceNumber,
+    #[cfg(feature = "pdf")]
+    PdfCharacter(char),
+    #[cfg(feature = "pdf")]
+    PdfFont,
+    InvoiceIssued(String),
+    RegistrationNumber,
+    NothingToInvoice,
+    UntaxedItem(String),
+    TaxRate,
+    #[cfg(feature = "png")]
+    Image(PathBuf, Cause),
+    #[cfg(feature = "png")]
+    Font,
+    #[cfg(feature = "png")]
+    Render,
+}
+
+/// The underlying error that a file-related [`Failure`] was caused by.
+#[derive(Debug)]
+pub enum Cause {
+    Io(io::Error),
+    Json(serde_json::Error),
+    Version(u64),
+    #[cfg(feature = "sqlite")]
+    Sqlite(rusqlite::Error),
+}
+
+impl Failure {
+    /// Every message from this failure down to the root cause, outermost first.
+    pub fn chain(&self) -> Vec<String> {
+        let mut retval = Vec::new();
+        let mut current: Option<&dyn Error> = Some(self);
+
+        while let Some(error) = current {
+            retval.push(error.to_string());
+            current = error.source();
+        }
+
+        retval
+    }
 }
 
 impl Display for Failure {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Cell(i) => write!(f, "cells[{i}] was referenced (supposed to be unreachable)"),
-            Self::Offset => write!(
-                f,
-                "Offset must be a number (u8) (unreachable due to unwrap_or(N/A))"
-            ),
-            Self::Year => write!(
-                f,
-                "Year must be a number (i32) (unreachable due to unwrap_or(N/A))"
-            ),
-            Self::Load => write!(f, "Load failed"),
-            Self::Save => write!(f, "Save failed"),
+            Self::NoFileSelected => write!(f, "No file selected"),
+            #[cfg(feature = "sqlite")]
+            Self::NoSuchSave(name) => write!(f, "\"{name}\" does not exist"),
+            Self::Load(path, _) => write!(f, "Load failed ({})", path.display()),
+            Self::Save(path, _) => write!(f, "Save failed ({})", path.display()),
             Self::SaveEmptyName => write!(f, "Cannot save without name"),
+            Self::Filename => write!(f, "Name must not contain separators or reserved characters"),
             Self::Pay => write!(f, "Pay must be a number (u32)"),
-            Self::FileRemove => write!(f, "Delete failed"),
+            Self::FileRemove(path, _) => write!(f, "Delete failed ({})", path.display()),
             Self::DurationParse => write!(f, "Duration must consist of numbers (u8)"),
             Self::Duration => write!(f, "Invalid duration"),
             Self::Date => write!(f, "Invalid date"),
+            Self::Settings(path, _) => {
+                write!(f, "Reading or writing settings failed ({})", path.display())
+            }
+            Self::Autosave(path, _) => write!(f, "Autosave failed ({})", path.display()),
+            Self::Backup(path, _) => write!(f, "Backup failed ({})", path.display()),
+            Self::BackupCount => write!(f, "Backup count must be a number (usize)"),
+            Self::UtcOffset => write!(f, "UTC offset must look like +09:00"),
+            Self::Goal => write!(f, "Goal must be a number (u32)"),
+            Self::Payslip => write!(
+                f,
+                "Payslip hours must look like 120:30 and amounts be numbers"
+            ),
+            Self::Report(path, _) => write!(f, "Writing the report failed ({})", path.display()),
+            Self::Invoice(path, _) => {
+                write!(f, "Writing the invoice failed ({})", path.display())
+            }
+            Self::InvoiceNames => write!(f, "Invoice needs issuer and client names"),
+            // the one after it has to fit as well
+            Self::InvoiceNumber => {
+                write!(f, "Invoice number must be a number up to {}", u32::MAX - 1)
+            }
+            #[cfg(feature = "pdf")]
+            Self::PdfCharacter(c) => write!(
+                f,
+                "PDF left out since no installed font can show \"{c}\"; the HTML has everything"
+            ),
+            #[cfg(feature = "pdf")]
+            Self::PdfFont => write!(f, "PDF left out since its font could not be embedded"),
+            Self::InvoiceIssued(number) => write!(f, "Invoice {number} was already issued"),
+            Self::RegistrationNumber => {
+                write!(f, "Registration number must be T followed by 13 digits")
+            }
+            Self::NothingToInvoice => write!(f, "No confirmed per-time shifts to invoice"),
+            Self::UntaxedItem(name) => write!(
+                f,
+                "\"{name}\" has no tax rate, which a qualified invoice needs"
+            ),
+            Self::TaxRate => write!(f, "Tax rate must be a percentage (0 to 100)"),
+            #[cfg(feature = "png")]
+            Self::Image(path, _) => write!(f, "Writing the image failed ({})", path.display()),
+            #[cfg(feature = "png")]
+            Self::Font => write!(f, "No usable system font found"),
+            #[cfg(feature = "png")]
+            Self::Render => write!(f, "Rendering the image failed"),
+        }
+    }
+}
+
+impl Error for Failure {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Load(_, cause)
+            | Self::Save(_, cause)
+            | Self::FileRemove(_, cause)
+            | Self::Settings(_, cause)
+            | Self::Autosave(_, cause)
+            | Self::Backup(_, cause)
+            | Self::Report(_, cause)
+            | Self::Invoice(_, cause) => Some(cause),
+            #[cfg(feature = "png")]
+            Self::Image(_, cause) => Some(cause),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Cause {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(_) => write!(f, "I/O error"),
+            // errors from `from_value` carry no position
+            Self::Json(e) if e.line() == 0 => write!(f, "Unexpected save contents"),
+            Self::Json(e) => write!(
+                f,
+                "Invalid JSON at line {}, column {}",
+                e.line(),
+                e.column()
+            ),
+            Self::Version(version) => write!(f, "Unsupported save version ({version})"),
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(_) => write!(f, "Database error"),
+        }
+    }
+}
+
+impl Error for Cause {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Json(e) => Some(e),
+            Self::Version(_) => None,
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(e) => Some(e),
         }
     }
 }
 
-impl Error for Failure {}
+impl From<io::Error> for Cause {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<serde_json::Error> for Cause {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Json(value)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl From<rusqlite::Error> for Cause {
+    fn from(value: rusqlite::Error) -> Self {
+        Self::Sqlite(value)
+    }
+}
@@ -0,0 +1,40 @@
+/// Families likely to cover Japanese, tried in order on Linux, macOS and
+/// Windows alike.
+const FALLBACK_FAMILIES: [&str; 10] = [
+    "Noto Sans CJK JP",
+    "Noto Sans JP",
+    "Source Han Sans",
+    "IPAGothic",
+    "Hiragino Sans",
+    "Yu Gothic",
+    "Meiryo",
+    "MS Gothic",
+    "WenQuanYi Zen Hei",
+    "Droid Sans Fallback",
+];
+
+pub fn system() -> fontdb::Database {
+    let mut retval = fontdb::Database::new();
+    retval.load_system_fonts();
+    retval
+}
+
+/// The installed faces of [`FALLBACK_FAMILIES`], in that order.
+pub fn fallbacks(database: &fontdb::Database) -> Vec<fontdb::ID> {
+    let mut retval = Vec::new();
+
+    for name in FALLBACK_FAMILIES {
+        let query = fontdb::Query {
+            families: &[fontdb::Family::Name(name)],
+            ..Default::default()
+        };
+
+        if let Some(id) = database.query(&query)
+            && !retval.contains(&id)
+        {
+            retval.push(id);
+        }
+    }
+
+    retval
+}
@@ -0,0 +1,411 @@
+use crate::{
+    cell::Cell,
+    config::{Tax, Type},
+    document::{Document, escape},
+    failure::Failure,
+    storage::Configs,
+    tax::Totals,
+    util,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use time::Date;
+
+/// Who bills whom, kept in the settings between invoices.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Details {
+    pub issuer: String,
+    pub address: String,
+    /// Qualified invoice issuer number (`T` and 13 digits). Optional, since
+    /// not every freelancer is registered.
+    pub registration_number: String,
+    pub client: String,
+    pub next_number: u32,
+}
+
+impl Default for Details {
+    fn default() -> Self {
+        Self {
+            issuer: String::new(),
+            address: String::new(),
+            registration_number: String::new(),
+            client: String::new(),
+            next_number: 1,
+        }
+    }
+}
+
+impl Details {
+    pub fn validate(&self) -> Result<(), Failure> {
+        let registration_number = self.registration_number.trim();
+
+        if self.issuer.trim().is_empty() || self.client.trim().is_empty() {
+            Err(Failure::InvoiceNames)
+        } else if !registration_number.is_empty() && !is_registration_number(registration_number) {
+            Err(Failure::RegistrationNumber)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// A qualified invoice has to state a rate for every line.
+    pub fn check_items(&self, items: &[Item]) -> Result<(), Failure> {
+        match items.iter().find(|x| x.tax.rate == 0) {
+            Some(item) if !self.registration_number.trim().is_empty() => {
+                Err(Failure::UntaxedItem(item.name.clone()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+pub fn is_registration_number(input: &str) -> bool {
+    input
+        .strip_prefix('T')
+        .is_some_and(|x| x.len() == 13 && x.bytes().all(|x| x.is_ascii_digit()))
+}
+
+pub struct Item {
+    pub name: String,
+    pub count: usize,
+    pub unit_price: u32,
+    pub tax: Tax,
+}
+
+impl Item {
+    pub fn amount(&self) -> u32 {
+        self.unit_price * self.count as u32
+    }
+}
+
+pub fn totals(items: &[Item]) -> Totals {
+    Totals::new(items.iter().map(|x| (x.amount(), x.tax)))
+}
+
+/// Amount and tax per rate, then the total, as printed below the items.
+pub fn summary(totals: &Totals) -> Vec<(String, u32)> {
+    let mut retval = Vec::new();
+
+    for x in &totals.rates {
+        if x.rate == 0 {
+            retval.push(("Not taxable".to_string(), x.pre_tax));
+        } else {
+            retval.push((format!("Subtotal ({}% taxable)", x.rate), x.pre_tax));
+            retval.push((format!("Consumption tax ({}%)", x.rate), x.tax));
+        }
+    }
+
+    retval.push(("Total".to_string(), totals.total()));
+    retval
+}
+
+/// Confirmed shifts of per-time configs, one line per config.
+pub fn items<'a>(configs: &Configs, cells: impl IntoIterator<Item = &'a Cell>) -> Vec<Item> {
+    let cells: Vec<_> = cells.into_iter().collect();
+
+    configs
+        .iter()
+        .filter(|(_, config)| config.r#type == Type::PerTime)
+        .map(|(name, config)| Item {
+            name: name.clone(),
+            count: cells.iter().filter(|x| x.is_confirmed(name)).count(),
+            unit_price: config.pay,
+            tax: config.tax,
+        })
+        .filter(|x| x.count > 0)
+        .collect()
+}
+
+pub struct Invoice<'a> {
+    pub number: u32,
+    pub issued: Date,
+    pub start: Date,
+    /// Exclusive.
+    pub end: Date,
+    pub details: &'a Details,
+    pub items: Vec<Item>,
+}
+
+impl Invoice<'_> {
+    pub fn number_to_string(&self) -> String {
+        format!("{:05}", self.number)
+    }
+
+    fn last(&self) -> Date {
+        self.end.previous_day().unwrap_or(self.end)
+    }
+
+    pub fn summary(&self) -> Vec<(String, u32)> {
+        summary(&totals(&self.items))
+    }
+}
+
+impl Document for Invoice<'_> {
+    fn stem(&self) -> String {
+        format!("invoice-{}", self.number_to_string())
+    }
+
+    fn to_html(&self) -> String {
+        let mut html = String::new();
+        let details = self.details;
+        let number = self.number_to_string();
+
+        let _ = write!(
+            html,
+            "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>Invoice {number}</title>
+<style>
+body {{ font-family: sans-serif; margin: 2em; max-width: 48em; }}
+table {{ border-collapse: collapse; }}
+.items {{ width: 100%; margin: 2em 0; }}
+.items th, .items td {{ border: 1px solid #999; padding: 4px 8px; }}
+.number {{ text-align: right; font-family: monospace; }}
+.client {{ font-size: 1.4em; border-bottom: 1px solid #000; }}
+.issuer {{ text-align: right; }}
+@media print {{ body {{ margin: 0; }} }}
+</style>
+</head>
+<body>
+<h1>請求書 / Invoice</h1>
+<table>
+<tr><th>No.</th><td>{number}</td></tr>
+<tr><th>Issued</th><td>{issued}</td></tr>
+<tr><th>Period</th><td>{start} - {last}</td></tr>
+</table>
+<p class=\"client\">{client} 御中</p>
+<div class=\"issuer\">
+<p>{issuer}<br>{address}</p>
+",
+            issued = self.issued,
+            start = self.start,
+            last = self.last(),
+            client = escape(&details.client),
+            issuer = escape(&details.issuer),
+            address = escape(&details.address).replace('\n', "<br>"),
+        );
+
+        if !details.registration_number.trim().is_empty() {
+            let _ = writeln!(
+                html,
+                "<p>登録番号 / Registration No. {}</p>",
+                escape(details.registration_number.trim())
+            );
+        }
+
+        html.push_str(
+            "</div>
+<table class=\"items\">
+<tr><th>Description</th><th>Tax</th><th>Qty</th><th>Unit price</th><th>Amount</th></tr>
+",
+        );
+
+        for item in &self.items {
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td>{}</td><td class=\"number\">{}</td>\
+                 <td class=\"number\">{}</td><td class=\"number\">{}</td></tr>",
+                escape(&item.name),
+                item.tax,
+                item.count,
+                util::yen(item.unit_price),
+                util::yen(item.amount())
+            );
+        }
+
+        for (label, amount) in self.summary() {
+            let _ = writeln!(
+                html,
+                "<tr><th colspan=\"4\">{label}</th><td class=\"number\">{}</td></tr>",
+                util::yen(amount)
+            );
+        }
+
+        html.push_str(
+            "</table>
+<p>Consumption tax is rounded down once per rate.</p>
+</body>
+</html>
+",
+        );
+
+        html
+    }
+
+    #[cfg(feature = "pdf")]
+    fn to_pdf(&self) -> Result<Vec<u8>, Failure> {
+        self.render_pdf()
+    }
+
+    #[cfg(feature = "pdf")]
+    fn pdf_texts(&self) -> Vec<&str> {
+        let details = self.details;
+
+        [
+            details.issuer.as_str(),
+            &details.address,
+            &details.registration_number,
+            &details.client,
+        ]
+        .into_iter()
+        .chain(self.items.iter().map(|x| x.name.as_str()))
+        .collect()
+    }
+}
+
+#[cfg(feature = "pdf")]
+mod pdf {
+    use super::Invoice;
+    use crate::{
+        document::{
+            Document,
+            pdf::{Pages, SANS},
+        },
+        failure::Failure,
+        util,
+    };
+    use iced::Color;
+
+    /// A4 portrait, in points.
+    const WIDTH: f32 = 595.0;
+    const HEIGHT: f32 = 842.0;
+    const MARGIN: f32 = 56.0;
+    const LINE_HEIGHT: f32 = 16.0;
+    const RIGHT: f32 = WIDTH - MARGIN;
+    /// Right edges of the quantity, unit price and amount columns.
+    const COLUMNS: [f32; 3] = [RIGHT - 200.0, RIGHT - 100.0, RIGHT];
+
+    /// Headings of the items table, which is repeated on every page it spans.
+    /// Returns where the first row goes.
+    fn table_header(pages: &mut Pages, y: f32) -> f32 {
+        pages.text(SANS, 9.0, MARGIN, y, "Description");
+
+        for (label, column) in ["Qty", "Unit price", "Amount"].into_iter().zip(COLUMNS) {
+            pages.text(SANS, 9.0, column - 50.0, y, label);
+        }
+
+        rule(pages, y - 5.0);
+        y - LINE_HEIGHT - 2.0
+    }
+
+    fn rule(pages: &mut Pages, y: f32) {
+        pages
+            .content()
+            .set_line_width(0.5)
+            .move_to(MARGIN, y)
+            .line_to(RIGHT, y)
+            .stroke();
+    }
+
+    /// The top of a page after the first.
+    fn next_page(pages: &mut Pages) -> f32 {
+        pages.next_page();
+        pages.fill(Color::BLACK);
+        HEIGHT - MARGIN - LINE_HEIGHT
+    }
+
+    impl Invoice<'_> {
+        pub fn render_pdf(&self) -> Result<Vec<u8>, Failure> {
+            let details = self.details;
+            let mut pages = Pages::new(WIDTH, HEIGHT, self.pdf_texts())?;
+            let mut y = HEIGHT - MARGIN - 20.0;
+
+            pages
+                .fill(Color::BLACK)
+                .text(SANS, 20.0, MARGIN, y, "INVOICE");
+
+            for (label, value) in [
+                ("No.", self.number_to_string()),
+                ("Issued", self.issued.to_string()),
+            ] {
+                pages.text(SANS, 9.0, RIGHT - 160.0, y, label);
+                pages.mono_right(9.0, RIGHT, y, &value);
+                y -= LINE_HEIGHT;
+            }
+
+            y -= LINE_HEIGHT;
+            pages.text(SANS, 14.0, MARGIN, y, &details.client);
+            pages
+                .content()
+                .set_line_width(0.5)
+                .move_to(MARGIN, y - 4.0)
+                .line_to(MARGIN + 260.0, y - 4.0)
+                .stroke();
+            y -= 2.0 * LINE_HEIGHT;
+
+            let issuer =
+                [details.issuer.as_str()]
+                    .into_iter()
+                    .chain(details.address.lines())
+                    .map(str::to_string)
+                    .chain((!details.registration_number.trim().is_empty()).then(|| {
+                        format!("Registration No. {}", details.registration_number.trim())
+                    }));
+
+            for line in issuer {
+                pages.text(SANS, 9.0, RIGHT - 200.0, y, &line);
+                y -= LINE_HEIGHT;
+            }
+
+            y -= LINE_HEIGHT;
+            pages.text(
+                SANS,
+                9.0,
+                MARGIN,
+                y,
+                &format!("Period: {} - {}", self.start, self.last()),
+            );
+            y -= LINE_HEIGHT * 1.5;
+            y = table_header(&mut pages, y);
+
+            for item in &self.items {
+                if y < MARGIN {
+                    let top = next_page(&mut pages);
+                    y = table_header(&mut pages, top);
+                }
+
+                pages.text(
+                    SANS,
+                    9.0,
+                    MARGIN,
+                    y,
+                    &format!("{} ({})", item.name, item.tax),
+                );
+                pages.mono_right(9.0, COLUMNS[0], y, &item.count.to_string());
+                pages.mono_right(9.0, COLUMNS[1], y, &util::yen(item.unit_price));
+                pages.mono_right(9.0, COLUMNS[2], y, &util::yen(item.amount()));
+                y -= LINE_HEIGHT;
+            }
+
+            rule(&mut pages, y + LINE_HEIGHT - 5.0);
+            y -= 4.0;
+
+            let summary = self.summary();
+
+            // kept together with the note below it
+            if y - (summary.len() + 1) as f32 * LINE_HEIGHT < MARGIN {
+                y = next_page(&mut pages);
+            }
+
+            for (label, amount) in summary {
+                pages.text(SANS, 9.0, COLUMNS[0] - 50.0, y, &label);
+                pages.mono_right(9.0, RIGHT, y, &util::yen(amount));
+                y -= LINE_HEIGHT;
+            }
+
+            y -= LINE_HEIGHT;
+            pages.text(
+                SANS,
+                8.0,
+                MARGIN,
+                y,
+                "Consumption tax is rounded down once per rate.",
+            );
+
+            pages.finish()
+        }
+    }
+}
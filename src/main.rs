@@ -2,88 +2,438 @@
 
 mod cell;
 mod config;
+mod document;
 mod failure;
+#[cfg(any(feature = "pdf", feature = "png"))]
+mod fonts;
+mod invoice;
+mod merge;
+mod offset;
+#[cfg(feature = "png")]
+mod raster;
+mod reconciliation;
+mod recovery;
+mod report;
+mod save_data;
+mod settings;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+mod statistics;
+mod storage;
+mod summary;
+mod tax;
 mod util;
+mod versioned;
+mod year;
 
-use cell::Cell;
-use config::{Config, HourMinute, Type, TypeForPickList};
+use cell::{Assigned, Cell, Status};
+use config::{Config, HourMinute, Tax, TaxBasis, Type, TypeForPickList};
 use failure::Failure;
-use iced::{Color, Element, Length, Size, Theme, alignment, theme, widget};
-use std::{collections::BTreeMap, fs};
-use time::{Date, Duration, Month, Weekday};
+use iced::{
+    Color, Element, Event, Length, Size, Subscription, Task, Theme, alignment, event, keyboard,
+    mouse, widget, window,
+};
+use invoice::Invoice;
+use merge::{Merge, Resolution};
+use offset::Offset;
+use reconciliation::{Breakdown, Line, Reconciliation};
+use recovery::{Recovery, Snapshot};
+use report::Report;
+use save_data::{PeriodRecord, SaveData};
+use settings::{Appearance, DefaultPeriod, Settings, WeekStart};
+use statistics::Statistics;
+use std::{
+    collections::{BTreeMap, VecDeque},
+    path::PathBuf,
+};
+use storage::{Backup, Checked, Configs, JsonStorage, Storage};
+use tax::Totals;
+use time::{Date, Duration, Month, OffsetDateTime, Time, UtcOffset, Weekday};
 use util::Some;
 
 struct App {
     month_selected: Option<Month>,
-    offset_input: String,
-    year_input: String,
+    offset_selected: Option<Offset>,
+    year: i32,
     name_input: String,
     type_selected: Option<TypeForPickList>,
     pay_input: String,
+    tax_basis_selected: TaxBasis,
+    tax_rate_input: String,
     hour_input: String,
     minute_input: String,
-    configs: BTreeMap<String, Config>,
-    cells: [Cell; Self::CALENDAR_COLUMNS as usize * Self::CALENDAR_ROWS as usize],
+    configs: Configs,
+    custom_range: Option<(Date, Date)>,
+    cells: Vec<Cell>,
     filename_input: String,
     filename_selected: Option<String>,
     title: String,
+    modifiers: keyboard::Modifiers,
+    anchor: Option<usize>,
+    drag: Option<Drag>,
+    settings: Settings,
+    /// The settings file did not load, so it is left alone rather than
+    /// overwritten with defaults until the next start.
+    settings_unreadable: bool,
+    local_offset: Option<UtcOffset>,
+    utc_offset_input: String,
+    data_dir_input: String,
+    confirm: Option<Confirm>,
+    failures: VecDeque<LoggedFailure>,
+    toast_visible: bool,
+    log_visible: bool,
+    recovered: Option<Snapshot>,
+    last_autosave: String,
+    autosave_failing: bool,
+    backup_count_input: String,
+    backups: Option<Vec<Backup>>,
+    periods: Vec<PeriodRecord>,
+    cells_period: Option<(Date, Date)>,
+    settings_visible: bool,
+    saves: Vec<String>,
+    saves_failing: bool,
+    merge: Option<Merge>,
+    year_view: bool,
+    per_time_input: String,
+    goal_input: String,
+    default_goal_input: String,
+    payslip: Option<PayslipForm>,
+    invoice_visible: bool,
+    invoice_number_input: String,
 }
 
-impl Default for App {
-    fn default() -> Self {
-        let current_date = util::current_date();
+enum Confirm {
+    Overwrite(String),
+    Delete(String),
+}
+
+struct LoggedFailure {
+    time: Time,
+    failure: Failure,
+}
+
+#[derive(Default)]
+struct PayslipInput {
+    hours: String,
+    amount: String,
+}
+
+/// Payslip lines being typed in for the period on screen.
+struct PayslipForm {
+    total: PayslipInput,
+    configs: BTreeMap<String, PayslipInput>,
+}
 
+impl PayslipInput {
+    fn new(line: &Line) -> Self {
         Self {
-            month_selected: match current_date {
-                Some(x) => x.month().previous(),
-                None => Month::January,
-            }
-            .some(),
-            offset_input: Default::default(),
-            year_input: current_date
-                .map(|x| x.year().to_string())
+            hours: line.minutes.map(util::hours).unwrap_or_default(),
+            amount: line.amount.map(|x| x.to_string()).unwrap_or_default(),
+        }
+    }
+
+    /// Empty inputs are unknown rather than zero.
+    fn parse(&self) -> Result<Line, Failure> {
+        let parse = |input: &str, f: fn(&str) -> Option<u32>| match input.trim() {
+            "" => Ok(None),
+            input => f(input).map(Some).ok_or(Failure::Payslip),
+        };
+
+        Ok(Line {
+            minutes: parse(&self.hours, util::parse_minutes)?,
+            amount: parse(&self.amount, util::parse_amount)?,
+        })
+    }
+}
+
+impl PayslipForm {
+    /// One row for every config expected or already on the stored payslip.
+    fn new(expected: &Breakdown, stored: Option<&Breakdown>) -> Self {
+        let line = |name: &str| {
+            stored
+                .and_then(|x| x.configs.get(name))
+                .map(PayslipInput::new)
+                .unwrap_or_default()
+        };
+
+        Self {
+            total: stored
+                .map(|x| PayslipInput::new(&x.total))
                 .unwrap_or_default(),
+            configs: expected
+                .configs
+                .keys()
+                .chain(stored.into_iter().flat_map(|x| x.configs.keys()))
+                .map(|name| (name.clone(), line(name)))
+                .collect(),
+        }
+    }
+
+    fn input_mut(&mut self, name: Option<String>) -> Option<&mut PayslipInput> {
+        match name {
+            Some(name) => self.configs.get_mut(&name),
+            None => Some(&mut self.total),
+        }
+    }
+
+    fn parse(&self) -> Result<Breakdown, Failure> {
+        let mut configs = BTreeMap::new();
+
+        for (name, input) in &self.configs {
+            let line = input.parse()?;
+
+            if line != Line::default() {
+                configs.insert(name.clone(), line);
+            }
+        }
+
+        Ok(Breakdown {
+            total: self.total.parse()?,
+            configs,
+        })
+    }
+
+    /// Like `parse`, but whatever does not parse yet is left out.
+    fn preview(&self) -> Breakdown {
+        Breakdown {
+            total: self.total.parse().unwrap_or_default(),
+            configs: self
+                .configs
+                .iter()
+                .map(|(name, input)| (name.clone(), input.parse().unwrap_or_default()))
+                .collect(),
+        }
+    }
+}
+
+struct Drag {
+    origin: usize,
+    base: Vec<bool>,
+}
+
+impl App {
+    /// Settings that failed to load are reported, and replaced by the defaults
+    /// for this session only.
+    fn new(local_offset: Option<UtcOffset>, loaded: Result<Settings, Failure>) -> Self {
+        let (settings, settings_failure) = match loaded {
+            Ok(settings) => (settings, None),
+            Err(failure) => (Settings::default(), Some(failure)),
+        };
+        let offset = local_offset
+            .or(settings.utc_offset())
+            .unwrap_or(UtcOffset::UTC);
+        let today = util::current_date(offset);
+        let initial = match settings.default_period {
+            DefaultPeriod::PreviousMonth => today.replace_day(1).ok().and_then(Date::previous_day),
+            DefaultPeriod::CurrentMonth | DefaultPeriod::ContainingToday => Some(today),
+        };
+
+        let mut app = Self {
+            month_selected: initial.map_or(Month::January, Date::month).some(),
+            offset_selected: Some(settings.offset),
+            year: initial.map_or(1970, Date::year),
             name_input: Default::default(),
-            type_selected: Some(TypeForPickList::PerHour),
+            type_selected: Some(settings.default_type),
             pay_input: Default::default(),
+            tax_basis_selected: Default::default(),
+            tax_rate_input: Default::default(),
             hour_input: Default::default(),
             minute_input: Default::default(),
             configs: Default::default(),
-            cells: std::array::from_fn(|_| Default::default()),
+            custom_range: None,
+            cells: Vec::new(),
             filename_input: Default::default(),
             filename_selected: Default::default(),
             title: "Calc".to_string(),
+            modifiers: Default::default(),
+            anchor: None,
+            drag: None,
+            utc_offset_input: settings
+                .utc_offset()
+                .map(|x| x.to_string())
+                .unwrap_or_default(),
+            data_dir_input: settings
+                .data_dir
+                .as_ref()
+                .map(|x| x.display().to_string())
+                .unwrap_or_default(),
+            backup_count_input: settings.backup_count.to_string(),
+            goal_input: String::new(),
+            default_goal_input: settings.goal.map(|x| x.to_string()).unwrap_or_default(),
+            per_time_input: util::hours(settings.per_time_minutes as u32),
+            invoice_number_input: settings.invoice.next_number.to_string(),
+            settings,
+            local_offset,
+            confirm: None,
+            failures: VecDeque::new(),
+            toast_visible: false,
+            log_visible: false,
+            recovered: None,
+            last_autosave: Default::default(),
+            autosave_failing: false,
+            backups: None,
+            periods: Vec::new(),
+            cells_period: None,
+            settings_visible: false,
+            saves: Vec::new(),
+            saves_failing: false,
+            settings_unreadable: settings_failure.is_some(),
+            merge: None,
+            payslip: None,
+            invoice_visible: false,
+            year_view: false,
+        };
+
+        if let Some(failure) = settings_failure {
+            app.set_failure(failure);
+        }
+
+        match app.recovery().load() {
+            Ok(recovered) => app.recovered = recovered,
+            Err(failure) => app.set_failure(failure),
         }
+
+        if !app.settings.legacy_imported {
+            app.import_legacy();
+        }
+
+        app.refresh_saves();
+        app.reset_cells();
+
+        if app.settings.default_period == DefaultPeriod::ContainingToday {
+            app.jump_to_today();
+        }
+
+        if let Some(filename) = app.settings.last_file.clone()
+            && app.saves.contains(&filename)
+        {
+            app.filename_input = filename.clone();
+            app.filename_selected = Some(filename);
+
+            match app.load() {
+                Ok(data) => app.open(data),
+                Err(failure) => app.set_failure(failure),
+            }
+        }
+
+        app
     }
 }
 
 #[derive(Debug, Clone)]
 enum Message {
     MonthSelected(Month),
-    OffsetInput(String),
-    YearInput(String),
+    SettingsToggled,
+    WeekStartSelected(WeekStart),
+    AppearanceSelected(Appearance),
+    DefaultPeriodSelected(DefaultPeriod),
+    DefaultTypeSelected(TypeForPickList),
+    PerTimeInput(String),
+    PerTimeSubmitted,
+    GoalInput(String),
+    GoalSubmitted,
+    DefaultGoalInput(String),
+    DefaultGoalSubmitted,
+    PayslipToggled,
+    PayslipHoursInput(Option<String>, String),
+    PayslipAmountInput(Option<String>, String),
+    PayslipSaved,
+    ReportPressed,
+    #[cfg(feature = "png")]
+    ImagePressed,
+    SummaryFormatSelected(summary::Format),
+    CopySummaryPressed,
+    InvoiceToggled,
+    InvoiceIssuerInput(String),
+    InvoiceAddressInput(String),
+    InvoiceRegistrationInput(String),
+    InvoiceClientInput(String),
+    InvoiceNumberInput(String),
+    InvoiceIssued,
+    WindowResized(Size),
+    SavesPolled,
+    TodayPressed,
+    YearViewToggled,
+    UtcOffsetInput(String),
+    UtcOffsetSubmitted,
+    PreviousPressed,
+    NextPressed,
+    CustomRangeToggled(bool),
+    RangeStartPicked(Date),
+    RangeEndPicked(Date),
+    OffsetSelected(Offset),
+    YearStepped(i32),
     NameInput(String),
     TypeSelected(TypeForPickList),
     PayInput(String),
+    TaxBasisSelected(TaxBasis),
+    TaxRateInput(String),
     PushPressed,
     RemovePressed(String),
     RemoveFilePressed,
     HourInput(String),
     MinuteInput(String),
     AddPressed(String),
-    CellChecked(bool, u8),
-    CellButtonPressed(String, u8),
+    CellChecked(bool, usize),
+    CellButtonPressed(String, usize),
+    CellButtonRightPressed(String, usize),
+    ConfirmUpToTodayPressed,
     DeselectPressed,
+    InvertPressed,
+    SelectAllPressed,
+    CellPressed(usize),
+    CellEntered(usize),
+    MouseReleased,
+    ModifiersChanged(keyboard::Modifiers),
+    DataDirInput(String),
+    DataDirSubmitted,
+    BackupCountInput(String),
+    BackupCountSubmitted,
+    BackupsToggled,
+    #[cfg(feature = "sqlite")]
+    BackendSelected(settings::Backend),
+    #[cfg(feature = "sqlite")]
+    ImportPressed,
+    BackupRestorePressed(usize),
     FilenameInput(String),
     FilenameSelected(String),
     SavePressed,
     LoadPressed,
+    MergePressed,
+    MergeResolutionSelected(usize, Resolution),
+    MergeApplied,
+    MergeCancelled,
+    ConfirmPressed,
+    CancelPressed,
+    RestorePressed,
+    DiscardPressed,
+    AutosaveTick,
+    CloseRequested(window::Id),
+    ToastDismissed,
+    LogToggled,
+    LogCleared,
     WeekdayPressed(Weekday),
 }
 
+impl Message {
+    /// Sent by hovering, timers and the window rather than by the user acting.
+    /// A button press is followed by `MouseReleased`, for one.
+    fn is_ambient(&self) -> bool {
+        matches!(
+            self,
+            Self::MouseReleased
+                | Self::ModifiersChanged(_)
+                | Self::CellEntered(_)
+                | Self::WindowResized(_)
+                | Self::SavesPolled
+                | Self::AutosaveTick
+        )
+    }
+}
+
 impl App {
-    const OFFSET_WIDTH: u16 = 80;
-    const YEAR_WIDTH: u16 = 80;
+    const YEAR_WIDTH: u16 = 56;
+    const UTC_OFFSET_WIDTH: u16 = 100;
     const NAME_WIDTH: u16 = 162;
     const PAY_WIDTH: u16 = 130;
     const DURATION_WIDTH: u16 = 81;
@@ -93,10 +443,21 @@ impl App {
     const CHECKBOX_SIZE: u16 = 28;
     const RESULT_SIZE: u16 = 32;
     const SPACING: u16 = 6;
+    const BACKUP_COUNT_WIDTH: u16 = 60;
+    const SETTINGS_LABEL_WIDTH: u16 = 140;
+    const TOAST_WIDTH: u16 = 520;
+    const LOG_HEIGHT: u16 = 240;
+    const CHART_HEIGHT: u16 = 240;
+    const PERIOD_LABEL_WIDTH: u16 = 140;
+    const GOAL_WIDTH: u16 = 130;
+    const TAX_RATE_WIDTH: u16 = 60;
+    const MAX_LOGGED_FAILURES: usize = 100;
+    const AUTOSAVE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+    const SAVES_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
     const PADDING: u16 = 16;
     const CALENDER_VERTICAL_SPACING: u16 = Self::SPACING * 4;
-    const CALENDAR_ROWS: u8 = 6;
-    const CALENDAR_COLUMNS: u8 = util::WEEKDAYS.len() as u8;
+    const MIN_CALENDAR_ROWS: usize = 6;
+    const CALENDAR_COLUMNS: usize = util::WEEKDAYS.len();
 
     fn reset_title(&mut self) {
         self.title = "Calc".to_string();
@@ -107,7 +468,29 @@ impl App {
     }
 
     fn set_failure(&mut self, failure: Failure) {
-        self.set_title(failure.to_string().as_ref())
+        let time = util::current_time(self.utc_offset());
+
+        self.failures.push_front(LoggedFailure { time, failure });
+        self.failures.truncate(Self::MAX_LOGGED_FAILURES);
+        self.toast_visible = true;
+    }
+
+    fn save_settings(&mut self) {
+        if self.settings_unreadable {
+            return;
+        }
+
+        if let Err(failure) = self.settings.save() {
+            self.set_failure(failure);
+        }
+    }
+
+    /// Remembers the save so that it is opened again at startup.
+    fn remember_file(&mut self, filename: Option<&str>) {
+        if self.settings.last_file.as_deref() != filename {
+            self.settings.last_file = filename.map(str::to_string);
+            self.save_settings();
+        }
     }
 
     fn get_cell(&self, i: usize) -> Result<&Cell, Failure> {
@@ -118,12 +501,9 @@ impl App {
         self.cells.get_mut(i).ok_or(Failure::Cell(i))
     }
 
-    fn year(&self) -> Result<i32, Failure> {
-        self.year_input.parse().map_err(|_| Failure::Year)
-    }
-
-    fn offset(&self) -> Result<u8, Failure> {
-        self.offset_input.parse().map_err(|_| Failure::Offset)
+    fn offset(&self) -> Offset {
+        self.offset_selected
+            .expect("unreachable because something is always selected")
     }
 
     fn month(&self) -> Month {
@@ -136,34 +516,178 @@ impl App {
             .expect("unreachable because something is always selected")
     }
 
-    fn date(&self) -> Result<Date, Failure> {
-        Date::from_calendar_date(self.year()?, self.month(), self.offset()?)
+    fn period_start(&self, year: i32, month: Month) -> Result<Date, Failure> {
+        Date::from_calendar_date(year, month, self.offset().day(year, month))
             .map_err(|_| Failure::Date)
     }
 
+    fn date(&self) -> Result<Date, Failure> {
+        self.period_start(self.year, self.month())
+    }
+
     fn pay(&self) -> Result<u32, Failure> {
         self.pay_input.parse().map_err(|_| Failure::Pay)
     }
 
-    fn first_sunday(&self) -> Result<Date, Failure> {
-        self.date().map(|x| match x.weekday() {
-            Weekday::Sunday => x,
-            _ => x.prev_occurrence(Weekday::Sunday),
+    /// Left empty, no tax.
+    fn tax(&self) -> Result<Tax, Failure> {
+        let rate = match self.tax_rate_input.trim() {
+            "" => 0,
+            input => input
+                .trim_end_matches('%')
+                .parse()
+                .ok()
+                .filter(|&x| x <= 100)
+                .ok_or(Failure::TaxRate)?,
+        };
+
+        Ok(Tax {
+            basis: self.tax_basis_selected,
+            rate,
+        })
+    }
+
+    fn utc_offset(&self) -> UtcOffset {
+        self.local_offset
+            .or(self.settings.utc_offset())
+            .unwrap_or(UtcOffset::UTC)
+    }
+
+    fn now(&self) -> OffsetDateTime {
+        OffsetDateTime::now_utc().to_offset(self.utc_offset())
+    }
+
+    fn today(&self) -> Date {
+        util::current_date(self.utc_offset())
+    }
+
+    /// Moves the period so that it contains today, keeping the current offset
+    /// or the length of the custom range.
+    fn jump_to_today(&mut self) {
+        let today = self.today();
+
+        if let Some((start, end)) = self.custom_range {
+            let length = (end - start).whole_days() + 1;
+            let periods = (today - start).whole_days().div_euclid(length);
+
+            if periods != 0 {
+                self.shift_period(periods);
+            }
+
+            return;
+        }
+
+        let (year, month) = if self.offset().day(today.year(), today.month()) <= today.day() {
+            (today.year(), today.month())
+        } else if today.month() == Month::January {
+            (today.year() - 1, Month::December)
+        } else {
+            (today.year(), today.month().previous())
+        };
+
+        if self.month_selected != Some(month) || self.year != year {
+            self.month_selected = Some(month);
+            self.year = year;
+            self.reset_cells();
+        }
+    }
+
+    /// Moves a monthly period by whole months, a custom range by its own length.
+    fn shift_period(&mut self, n: i64) {
+        match self.custom_range {
+            Some((start, end)) => {
+                let shift = Duration::days(n * ((end - start).whole_days() + 1));
+
+                match start.checked_add(shift).zip(end.checked_add(shift)) {
+                    Some(range) => self.custom_range = Some(range),
+                    None => return self.set_failure(Failure::Date),
+                }
+            }
+            None => {
+                let months = self.year as i64 * 12 + self.month() as i64 - 1 + n;
+                let month = util::MONTHS[months.rem_euclid(12) as usize];
+                let year = months.div_euclid(12) as i32;
+
+                if let Err(failure) = self.period_start(year, month) {
+                    return self.set_failure(failure);
+                }
+
+                self.month_selected = Some(month);
+                self.year = year;
+            }
+        }
+
+        self.reset_cells();
+    }
+
+    fn toggle_custom_range(&mut self, enabled: bool) {
+        self.custom_range = if enabled {
+            let start = self.highlight_begin().unwrap_or_else(|_| self.today());
+            let end = self
+                .highlight_end()
+                .ok()
+                .and_then(Date::previous_day)
+                .filter(|end| &start <= end)
+                .unwrap_or(start);
+
+            Some((start, end))
+        } else {
+            None
+        };
+
+        self.reset_cells();
+    }
+
+    fn week_start(&self) -> Weekday {
+        self.settings.week_start.weekday()
+    }
+
+    fn first_week_start(&self) -> Result<Date, Failure> {
+        let week_start = self.week_start();
+
+        self.highlight_begin().map(|x| {
+            if x.weekday() == week_start {
+                x
+            } else {
+                x.prev_occurrence(week_start)
+            }
         })
     }
 
     fn highlight_begin(&self) -> Result<Date, Failure> {
-        self.date()
+        match self.custom_range {
+            Some((start, _)) => Ok(start),
+            None => self.date(),
+        }
     }
 
+    /// Exclusive.
     fn highlight_end(&self) -> Result<Date, Failure> {
-        match self.month() {
-            Month::December => {
-                Date::from_calendar_date(self.year()? + 1, Month::January, self.offset()?)
-            }
-            month => Date::from_calendar_date(self.year()?, month.next(), self.offset()?),
+        if let Some((_, end)) = self.custom_range {
+            return end.next_day().ok_or(Failure::Date);
         }
-        .map_err(|_| Failure::Date)
+
+        self.month_bounds(self.year, self.month())
+            .map(|(_, end)| end)
+    }
+
+    /// The monthly period starting in `month`, end exclusive.
+    fn month_bounds(&self, year: i32, month: Month) -> Result<(Date, Date), Failure> {
+        let end = match month {
+            Month::December => self.period_start(year + 1, Month::January),
+            month => self.period_start(year, month.next()),
+        };
+
+        Ok((self.period_start(year, month)?, end?))
+    }
+
+    fn year_totals(&self) -> Vec<year::PeriodTotal> {
+        let bounds = util::MONTHS.into_iter().filter_map(|month| {
+            let (start, end) = self.month_bounds(self.year, month).ok()?;
+            Some((month, start, end))
+        });
+
+        year::totals(bounds, &self.configs, &self.periods_with_current())
     }
 
     fn is_highlighted(&self, date: &Date) -> bool {
@@ -187,18 +711,264 @@ impl App {
                 TypeForPickList::PerTime => Type::PerTime,
                 TypeForPickList::PerHour => Type::PerHour(self.duration()?),
             },
+            tax: self.tax()?,
         })
     }
 
-    fn clear_cells(&mut self) {
+    /// Of the period on screen, planned shifts included.
+    fn tax_totals(&self) -> Totals {
+        Totals::of(&self.configs, |name| {
+            self.cells.iter().filter(|x| x.contains(name)).count()
+        })
+    }
+
+    fn calendar_rows(&self) -> usize {
+        let needed = match (self.first_week_start(), self.highlight_end()) {
+            (Ok(first), Ok(end)) => {
+                ((end - first).whole_days() as usize).div_ceil(Self::CALENDAR_COLUMNS)
+            }
+            _ => 0,
+        };
+
+        needed.max(Self::MIN_CALENDAR_ROWS)
+    }
+
+    /// Assignments of the period on screen, by date.
+    fn assignments(&self) -> Vec<(Date, Assigned)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(_, cell)| !cell.config_names.is_empty())
+            .filter_map(|(i, cell)| Some((self.cell_date(i).ok()?, cell.config_names.clone())))
+            .collect()
+    }
+
+    /// The stored records with the period on screen written into them.
+    fn periods_with_current(&self) -> Vec<PeriodRecord> {
+        let mut retval = self.periods.clone();
+
+        if let Some((start, end)) = self.cells_period {
+            let stored = retval.iter().find(|x| (x.start, x.end) == (start, end));
+            let reconciliation = stored.and_then(|x| x.reconciliation.clone());
+            let goal = stored.and_then(|x| x.goal);
+
+            retval.retain(|x| (x.start, x.end) != (start, end));
+
+            let assignments = self.assignments();
+
+            if !assignments.is_empty() || reconciliation.is_some() || goal.is_some() {
+                retval.push(PeriodRecord {
+                    start,
+                    end,
+                    assignments,
+                    reconciliation,
+                    goal,
+                });
+                retval.sort_by_key(|x| (x.start, x.end));
+            }
+        }
+
+        retval
+    }
+
+    /// Stashes the assignments of the period the cells were showing, then
+    /// resizes the grid and brings up the assignments of the current period.
+    fn reset_cells(&mut self) {
+        self.periods = self.periods_with_current();
+
+        let len = self.calendar_rows() * Self::CALENDAR_COLUMNS;
+
+        self.cells.resize_with(len, Default::default);
+        self.anchor = None;
+        self.payslip = None;
+
         for cell in &mut self.cells {
             cell.clear();
         }
+
+        self.cells_period = self.highlight_begin().ok().zip(self.highlight_end().ok());
+
+        let assignments = self
+            .periods
+            .iter()
+            .find(|x| Some((x.start, x.end)) == self.cells_period)
+            .map(|x| x.assignments.clone())
+            .unwrap_or_default();
+
+        self.apply_assignments(assignments);
+        self.goal_input = self
+            .current_record()
+            .and_then(|x| x.goal)
+            .map(|x| x.to_string())
+            .unwrap_or_default();
     }
 
-    fn clear_added(&mut self) {
-        for cell in &mut self.cells {
-            cell.clear_added();
+    fn apply_assignments(&mut self, assignments: Vec<(Date, Assigned)>) {
+        let Ok(first) = self.first_week_start() else {
+            return;
+        };
+
+        for (date, names) in assignments {
+            let Ok(i) = usize::try_from((date - first).whole_days()) else {
+                continue;
+            };
+
+            if let Ok(cell) = self.get_cell_mut(i) {
+                cell.config_names = names;
+            }
+        }
+    }
+
+    /// The stored record of the period on screen.
+    fn current_record(&self) -> Option<&PeriodRecord> {
+        self.periods
+            .iter()
+            .find(|x| Some((x.start, x.end)) == self.cells_period)
+    }
+
+    fn reconciliation(&self) -> Option<&Reconciliation> {
+        self.current_record()
+            .and_then(|x| x.reconciliation.as_ref())
+    }
+
+    /// The goal of the period on screen, or the default from the settings.
+    fn goal(&self) -> Option<u32> {
+        self.current_record()
+            .and_then(|x| x.goal)
+            .or(self.settings.goal)
+    }
+
+    fn reports_dir(&self) -> PathBuf {
+        self.settings.data_dir().join("reports")
+    }
+
+    /// The period on screen, named after the open save.
+    fn report(&self) -> Option<Report<'_>> {
+        let (start, end) = self.cells_period?;
+
+        Some(Report {
+            // becomes a file name
+            title: self
+                .filename_selected
+                .clone()
+                .filter(|x| storage::validate_name(x).is_ok())
+                .unwrap_or_else(|| "Calc".to_string()),
+            start,
+            end,
+            week_start: self.week_start(),
+            configs: &self.configs,
+            assignments: self
+                .assignments()
+                .into_iter()
+                .filter(|(date, _)| start <= *date && *date < end)
+                .collect(),
+        })
+    }
+
+    /// Of the save `name`, or else of the last file, in the period shown at
+    /// startup. Whatever else failed at startup does not matter here.
+    #[cfg(feature = "png")]
+    fn export_png(mut self, name: Option<String>) -> Result<PathBuf, Failure> {
+        let name = name.or_else(|| self.settings.last_file.clone());
+
+        if name.is_some() {
+            self.filename_selected = name;
+            let data = self.load()?;
+            self.open(data);
+        }
+
+        self.report()
+            .ok_or(Failure::Date)?
+            .write_png(&self.reports_dir())
+    }
+
+    fn invoices_dir(&self) -> PathBuf {
+        self.settings.data_dir().join("invoices")
+    }
+
+    /// Billing the period on screen under the number typed in.
+    fn invoice(&self) -> Result<Invoice<'_>, Failure> {
+        let details = &self.settings.invoice;
+        details.validate()?;
+
+        let number = self
+            .invoice_number_input
+            .trim()
+            .parse()
+            .map_err(|_| Failure::InvoiceNumber)?;
+        let items = invoice::items(&self.configs, &self.cells);
+        details.check_items(&items)?;
+
+        match self.cells_period {
+            Some((start, end)) if !items.is_empty() => Ok(Invoice {
+                number,
+                issued: self.today(),
+                start,
+                end,
+                details,
+                items,
+            }),
+            _ => Err(Failure::NothingToInvoice),
+        }
+    }
+
+    fn expected(&self) -> Breakdown {
+        Breakdown::expected(&self.configs, &self.cells)
+    }
+
+    /// The record of the period on screen, created if there is none yet.
+    fn current_record_mut(&mut self) -> Option<&mut PeriodRecord> {
+        let (start, end) = self.cells_period?;
+
+        self.periods = self.periods_with_current();
+
+        if !self
+            .periods
+            .iter()
+            .any(|x| (x.start, x.end) == (start, end))
+        {
+            self.periods.push(PeriodRecord {
+                start,
+                end,
+                assignments: Vec::new(),
+                reconciliation: None,
+                goal: None,
+            });
+            self.periods.sort_by_key(|x| (x.start, x.end));
+        }
+
+        self.periods
+            .iter_mut()
+            .find(|x| (x.start, x.end) == (start, end))
+    }
+
+    /// Highlighted days from today on that could still take a shift of
+    /// `name`, i.e. do not have it assigned yet. Other shifts on the same day
+    /// do not count against it.
+    fn free_days(&self, name: &str) -> usize {
+        let today = self.today();
+
+        (0..self.cells.len())
+            .filter(|&i| self.is_cell_highlighted(i))
+            .filter(|&i| self.cell_date(i).is_ok_and(|date| today <= date))
+            .filter(|&i| self.get_cell(i).is_ok_and(|x| !x.contains(name)))
+            .count()
+    }
+
+    /// In the period on screen and in every stored one.
+    fn confirm_up_to_today(&mut self) {
+        let today = self.today();
+
+        for (date, names) in self.periods.iter_mut().flat_map(|x| &mut x.assignments) {
+            if *date <= today {
+                names.values_mut().for_each(|x| *x = Status::Confirmed);
+            }
+        }
+
+        for i in 0..self.cells.len() {
+            if self.is_cell_highlighted(i) && self.cell_date(i).is_ok_and(|x| x <= today) {
+                self.get_cell_mut(i).expect("unreachable").confirm_all();
+            }
         }
     }
 
@@ -208,60 +978,237 @@ impl App {
         }
     }
 
-    fn load(&self) -> Result<BTreeMap<String, Config>, Failure> {
-        let filename = self.filename_selected.as_ref().ok_or(Failure::Load)?;
-        let read = fs::read_to_string(format!("{}.json", filename)).map_err(|_| Failure::Load)?;
-        serde_json::from_str(&read).map_err(|_| Failure::Load)
+    fn is_cell_highlighted(&self, i: usize) -> bool {
+        self.cell_date(i)
+            .map(|date| self.is_highlighted(&date))
+            .unwrap_or(false)
+    }
+
+    fn select_range(&mut self, a: usize, b: usize, selected: bool) {
+        for i in a.min(b)..=a.max(b) {
+            if self.is_cell_highlighted(i) {
+                self.get_cell_mut(i).expect("unreachable").selected = selected;
+            }
+        }
+    }
+
+    fn select_all(&mut self) {
+        for i in 0..self.cells.len() {
+            if self.is_cell_highlighted(i) {
+                self.get_cell_mut(i).expect("unreachable").select();
+            }
+        }
     }
 
-    fn save(&self) -> Result<(), Failure> {
-        if self.filename_input.is_empty() {
-            return Err(Failure::SaveEmptyName);
+    fn invert_selection(&mut self) {
+        for i in 0..self.cells.len() {
+            if self.is_cell_highlighted(i) {
+                let cell = self.get_cell_mut(i).expect("unreachable");
+                cell.selected = !cell.selected;
+            }
         }
+    }
+
+    fn storage(&self) -> Box<dyn Storage> {
+        let data_dir = self.settings.data_dir();
 
-        match serde_json::to_string(&self.configs) {
-            Ok(to_write) => fs::write(format!("{}.json", self.filename_input), to_write)
-                .map_err(|_| Failure::Save),
-            Err(_) => Err(Failure::Save),
+        match self.settings.backend {
+            #[cfg(feature = "sqlite")]
+            settings::Backend::Sqlite => Box::new(Checked(sqlite::SqliteStorage::new(&data_dir))),
+            _ => Box::new(Checked(JsonStorage::new(&data_dir))),
+        }
+    }
+
+    fn load(&self) -> Result<SaveData, Failure> {
+        let filename = self
+            .filename_selected
+            .as_ref()
+            .ok_or(Failure::NoFileSelected)?;
+        self.storage().load(filename)
+    }
+
+    fn open(&mut self, SaveData { configs, periods }: SaveData) {
+        self.configs = configs;
+        self.periods = periods;
+        self.cells_period = None;
+        // the preview was worked out against the configs being replaced
+        self.merge = None;
+        self.reset_cells();
+    }
+
+    /// Backs up the existing save before overwriting it.
+    fn save(&mut self, filename: &str) -> Result<(), Failure> {
+        let data = SaveData {
+            configs: self.configs.clone(),
+            periods: self.periods_with_current(),
+        };
+        let storage = self.storage();
+
+        storage.backup(filename, self.now(), self.settings.backup_count)?;
+        storage.save(filename, &data)?;
+
+        self.set_title("Configurations saved");
+        self.refresh_saves();
+        self.remember_file(Some(filename));
+        Ok(())
+    }
+
+    /// Asks for confirmation first if the save already exists.
+    fn request_save(&mut self) -> Result<(), Failure> {
+        let filename = self.filename_input.clone();
+
+        if self.storage().exists(&filename)? {
+            self.confirm = Some(Confirm::Overwrite(filename));
+            Ok(())
+        } else {
+            self.save(&filename)
         }
     }
 
     fn cell_date(&self, i: usize) -> Result<Date, Failure> {
-        self.first_sunday()
+        self.first_week_start()
             .and_then(|x| x.checked_add(Duration::days(i as i64)).ok_or(Failure::Date))
     }
 
-    fn find_jsons(&self) -> Result<Vec<String>, Failure> {
-        let dir = fs::read_dir("./").map_err(|_| Failure::Load)?;
+    fn recovery(&self) -> Recovery {
+        Recovery::new(&self.settings.data_dir())
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            month: self.month(),
+            offset: self.offset(),
+            year: self.year,
+            custom_range: self.custom_range,
+            configs: self.configs.clone(),
+            assignments: self.assignments(),
+            periods: self.periods.clone(),
+            name_input: self.name_input.clone(),
+            type_selected: self.r#type(),
+            pay_input: self.pay_input.clone(),
+            tax_basis_selected: self.tax_basis_selected,
+            tax_rate_input: self.tax_rate_input.clone(),
+            hour_input: self.hour_input.clone(),
+            minute_input: self.minute_input.clone(),
+            filename_input: self.filename_input.clone(),
+            filename_selected: self.filename_selected.clone(),
+        }
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.month_selected = Some(snapshot.month);
+        self.offset_selected = Some(snapshot.offset);
+        self.year = snapshot.year;
+        self.custom_range = snapshot.custom_range;
+        self.configs = snapshot.configs;
+        self.name_input = snapshot.name_input;
+        self.type_selected = Some(snapshot.type_selected);
+        self.pay_input = snapshot.pay_input;
+        self.tax_basis_selected = snapshot.tax_basis_selected;
+        self.tax_rate_input = snapshot.tax_rate_input;
+        self.hour_input = snapshot.hour_input;
+        self.minute_input = snapshot.minute_input;
+        self.filename_input = snapshot.filename_input;
+        self.filename_selected = snapshot.filename_selected;
+        self.periods = snapshot.periods;
+        self.cells_period = None;
+        self.reset_cells();
+        self.apply_assignments(snapshot.assignments);
+    }
+
+    /// Writes only when something changed unless `force` is set. Paused while
+    /// the recovery offer is pending so that it is not overwritten.
+    fn autosave(&mut self, force: bool) {
+        if self.recovered.is_some() {
+            return;
+        }
+
+        let Ok(to_write) = recovery::to_string(&self.snapshot()) else {
+            return;
+        };
 
-        let mut retval = Vec::new();
+        if !force && to_write == self.last_autosave {
+            return;
+        }
+
+        match self.recovery().save(&to_write) {
+            Ok(()) => {
+                self.last_autosave = to_write;
+                self.autosave_failing = false;
+            }
+            Err(failure) => {
+                if !self.autosave_failing {
+                    self.set_failure(failure);
+                }
+
+                self.autosave_failing = true;
+            }
+        }
+    }
+
+    /// Tried at every startup until it succeeds. The originals are left in
+    /// place.
+    fn import_legacy(&mut self) {
+        match storage::import_legacy(&*self.storage()) {
+            Ok(names) => {
+                if !names.is_empty() {
+                    self.set_title(&format!(
+                        "Imported {} saves from the working directory",
+                        names.len()
+                    ));
+                }
+
+                self.settings.legacy_imported = true;
+                self.save_settings();
+            }
+            Err(failure) => self.set_failure(failure),
+        }
+    }
 
-        for entry in dir {
-            let path = entry.map_err(|_| Failure::Load)?.path();
+    /// Reports a failure only once until listing works again, since this
+    /// also runs on a timer.
+    fn refresh_saves(&mut self) {
+        match self.storage().list() {
+            Ok(saves) => {
+                self.saves = saves;
+                self.saves_failing = false;
+            }
+            Err(failure) => {
+                self.saves.clear();
 
-            if let Some("json") = path.extension().and_then(|x| x.to_str()) {
-                if let Some(filename) = path.file_stem().and_then(|x| x.to_str()) {
-                    retval.push(filename.to_string())
+                if !self.saves_failing {
+                    self.set_failure(failure);
                 }
+
+                self.saves_failing = true;
             }
         }
+    }
+
+    /// Backs up the save before deleting it.
+    fn remove_file(&self, filename: &str) -> Result<(), Failure> {
+        let storage = self.storage();
 
-        Ok(retval)
+        storage.backup(filename, self.now(), self.settings.backup_count)?;
+        storage.remove(filename)
     }
 
-    fn remove_file(&self) -> Result<(), Failure> {
-        let filename = self.filename_selected.as_ref().ok_or(Failure::FileRemove)?;
-        fs::remove_file(format!("{filename}.json")).map_err(|_| Failure::FileRemove)
+    /// Only does anything while the backup browser is open.
+    fn refresh_backups(&mut self) {
+        if self.backups.is_some() {
+            match self.storage().backups() {
+                Ok(backups) => self.backups = Some(backups),
+                Err(failure) => self.set_failure(failure),
+            }
+        }
     }
 
-    fn calendar_cell(&self, r: u8, c: u8) -> Element<Message> {
-        use widget::{checkbox, column, row, text};
+    fn calendar_cell(&self, r: usize, c: usize) -> Element<'_, Message> {
+        use widget::{checkbox, column, mouse_area, row, text};
 
         let nth = r * Self::CALENDAR_COLUMNS + c;
-        let cell = self
-            .get_cell(nth as usize)
-            .expect("supposed to be unreachable");
-        let date = self.cell_date(nth as usize);
+        let cell = self.get_cell(nth).expect("supposed to be unreachable");
+        let date = self.cell_date(nth).ok();
         let active = date.map(|x| self.is_highlighted(&x)).unwrap_or(false);
 
         let chkbox = {
@@ -284,7 +1231,7 @@ impl App {
                     x.day().to_string()
                 }
             })
-            .unwrap_or_else(|_| "N/A".to_string());
+            .unwrap_or_else(|| "N/A".to_string());
 
         let date_text = text(date_str).width(Length::Fill).style(if active {
             text::base
@@ -292,47 +1239,447 @@ impl App {
             text::secondary
         });
 
-        column![
+        let content = column![
             row![chkbox, date_text],
-            column(cell.config_names.iter().map(|name| {
-                util::colored_button(
-                    text(name.as_str())
-                        .width(Length::Fill)
-                        .align_x(alignment::Horizontal::Center),
-                    util::get_color(name),
-                )
+            column(cell.config_names.iter().map(|(name, status)| {
+                let color = util::get_color(name);
+                let label = text(name.as_str())
+                    .width(Length::Fill)
+                    .align_x(alignment::Horizontal::Center);
+
+                // planned shifts are faded until confirmed
+                let button = match status {
+                    Status::Planned => util::colored_button(label, Color { a: 0.35, ..color }),
+                    Status::Confirmed => util::colored_button(label, color),
+                }
                 .padding(0)
-                .on_press(Message::CellButtonPressed(name.to_owned(), nth))
-                .into()
+                .on_press(Message::CellButtonPressed(name.to_owned(), nth));
+
+                mouse_area(button)
+                    .on_right_press(Message::CellButtonRightPressed(name.to_owned(), nth))
+                    .into()
             }))
             .spacing(Self::SPACING)
         ]
         .spacing(Self::SPACING)
         .padding(Self::SPACING)
+        .width(Length::Fill);
+
+        let content: Element<Message> = if date.is_some_and(|x| x == self.today()) {
+            util::outlined_container(content).into()
+        } else {
+            content.into()
+        };
+
+        if active {
+            mouse_area(content)
+                .on_press(Message::CellPressed(nth))
+                .on_enter(Message::CellEntered(nth))
+                .into()
+        } else {
+            content
+        }
+    }
+
+    fn payslip_view(&self) -> Element<'_, Message> {
+        use widget::{button, column, row, text, text_input};
+
+        let summary = match self.reconciliation() {
+            None => text("No payslip entered for this period"),
+            Some(reconciliation) => match reconciliation.discrepancies() {
+                0 => text(format!(
+                    "Payslip checked {}: matches",
+                    reconciliation.checked
+                )),
+                n => text(format!(
+                    "Payslip checked {}: {n} discrepancies",
+                    reconciliation.checked
+                ))
+                .style(text::danger),
+            },
+        };
+
+        let top = row![
+            summary.width(Length::Fill),
+            button(if self.payslip.is_some() {
+                "Close"
+            } else {
+                "Payslip"
+            })
+            .style(button::secondary)
+            .on_press(Message::PayslipToggled),
+        ]
+        .align_y(alignment::Vertical::Center)
+        .spacing(Self::SPACING);
+
+        let form = self.payslip.as_ref().map(|form| {
+            let expected = self.expected();
+            let payslip = form.preview();
+            let discrepancies = expected.discrepancies(&payslip);
+            let none = Line::default();
+
+            let line = |label: &str, name: Option<&String>, input: &PayslipInput| {
+                let expected = match name {
+                    Some(name) => expected.configs.get(name).unwrap_or(&none),
+                    None => &expected.total,
+                };
+                let label = text(label.to_string()).width(Length::Fill);
+                let label = if discrepancies.contains(&name.map(String::as_str)) {
+                    label.style(text::danger)
+                } else {
+                    label
+                };
+                let key = name.cloned();
+
+                row![
+                    label,
+                    util::monospace_text(expected.minutes.map(util::hours).unwrap_or_default())
+                        .width(Self::DURATION_WIDTH)
+                        .align_x(alignment::Horizontal::Right),
+                    util::monospace_text(expected.amount.map(util::yen).unwrap_or_default())
+                        .width(Self::SUM_WIDTH)
+                        .align_x(alignment::Horizontal::Right),
+                    text_input("Hours", &input.hours)
+                        .width(Self::DURATION_WIDTH)
+                        .on_input({
+                            let key = key.clone();
+                            move |x| Message::PayslipHoursInput(key.clone(), x)
+                        }),
+                    text_input("Amount", &input.amount)
+                        .width(Self::SUM_WIDTH)
+                        .on_input(move |x| Message::PayslipAmountInput(key.clone(), x)),
+                ]
+                .align_y(alignment::Vertical::Center)
+                .spacing(Self::SPACING)
+                .into()
+            };
+
+            let header = row![
+                text("Confirmed shifts (tax incl.) vs payslip").width(Length::Fill),
+                text("Hours").width(Self::DURATION_WIDTH),
+                text("Expected").width(Self::SUM_WIDTH),
+                text("Hours").width(Self::DURATION_WIDTH),
+                text("Payslip").width(Self::SUM_WIDTH),
+            ]
+            .spacing(Self::SPACING);
+
+            util::rounded_container(
+                column![header]
+                    .extend(
+                        form.configs
+                            .iter()
+                            .map(|(name, input)| line(name, Some(name), input)),
+                    )
+                    .push(line("Total", None, &form.total))
+                    .push(button("Save Reconciliation").on_press(Message::PayslipSaved))
+                    .spacing(Self::SPACING),
+            )
+            .padding(Self::SPACING)
+        });
+
+        column![top].push_maybe(form).spacing(Self::SPACING).into()
+    }
+
+    fn invoice_view(&self) -> Element<'_, Message> {
+        use widget::{button, column, row, text, text_input};
+
+        let items = invoice::items(&self.configs, &self.cells);
+        let totals = invoice::totals(&items);
+
+        let top = row![
+            text(format!(
+                "Per-time work to invoice: {} + tax {}",
+                util::yen(totals.pre_tax()),
+                util::yen(totals.tax())
+            ))
+            .width(Length::Fill),
+            button(if self.invoice_visible {
+                "Close"
+            } else {
+                "Invoice"
+            })
+            .style(button::secondary)
+            .on_press(Message::InvoiceToggled),
+        ]
+        .align_y(alignment::Vertical::Center)
+        .spacing(Self::SPACING);
+
+        let form = self.invoice_visible.then(|| {
+            let details = &self.settings.invoice;
+            let amount = |label: String, amount: u32| {
+                row![
+                    text(label).width(Length::Fill),
+                    util::monospace_text(util::yen(amount))
+                        .width(Self::SUM_WIDTH)
+                        .align_x(alignment::Horizontal::Right),
+                ]
+                .spacing(Self::SPACING)
+                .into()
+            };
+
+            util::rounded_container(
+                column![
+                    Self::setting(
+                        "Issuer",
+                        text_input("Name", &details.issuer).on_input(Message::InvoiceIssuerInput),
+                    ),
+                    Self::setting(
+                        "Address",
+                        text_input("Address", &details.address)
+                            .on_input(Message::InvoiceAddressInput),
+                    ),
+                    Self::setting(
+                        "Registration No.",
+                        text_input("T1234567890123", &details.registration_number)
+                            .on_input(Message::InvoiceRegistrationInput),
+                    ),
+                    Self::setting(
+                        "Client",
+                        text_input("Name", &details.client).on_input(Message::InvoiceClientInput),
+                    ),
+                    Self::setting(
+                        "Invoice number",
+                        text_input("1", &self.invoice_number_input)
+                            .width(Self::PAY_WIDTH)
+                            .on_input(Message::InvoiceNumberInput),
+                    ),
+                ]
+                .extend(items.iter().map(|item| {
+                    amount(
+                        format!(
+                            "{} x{} @ {}",
+                            item.name,
+                            item.count,
+                            util::yen(item.unit_price)
+                        ),
+                        item.amount(),
+                    )
+                }))
+                .extend(
+                    invoice::summary(&totals)
+                        .into_iter()
+                        .map(|(label, x)| amount(label, x)),
+                )
+                .push(button("Issue Invoice").on_press(Message::InvoiceIssued))
+                .spacing(Self::SPACING),
+            )
+            .padding(Self::SPACING)
+        });
+
+        column![top].push_maybe(form).spacing(Self::SPACING).into()
+    }
+
+    fn year_overview(&self) -> Element<'_, Message> {
+        use widget::{canvas, column, row, text};
+
+        let totals = self.year_totals();
+
+        let rows = column(totals.iter().map(|total| {
+            let breakdown = total
+                .configs
+                .iter()
+                .map(|x| format!("{} x{} ({})", x.name, x.count, util::yen(x.with_tax())))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            column![
+                row![
+                    util::monospace_text(total.label()).width(Self::PERIOD_LABEL_WIDTH),
+                    util::monospace_text(util::hours(total.minutes))
+                        .width(Self::DURATION_WIDTH)
+                        .align_x(alignment::Horizontal::Right),
+                    util::monospace_text(util::yen(total.sum()))
+                        .width(Length::Fill)
+                        .align_x(alignment::Horizontal::Right),
+                ]
+                .spacing(Self::SPACING),
+            ]
+            .push_maybe((!breakdown.is_empty()).then(|| text(breakdown).size(12)))
+            .into()
+        }))
+        .spacing(Self::SPACING);
+
+        let minutes = totals.iter().map(|x| x.minutes).sum();
+        let sum = totals.iter().map(year::PeriodTotal::sum).sum();
+
+        let year_total = row![
+            util::bold_text("Total").width(Self::PERIOD_LABEL_WIDTH),
+            util::monospace_text(util::hours(minutes))
+                .width(Self::DURATION_WIDTH)
+                .align_x(alignment::Horizontal::Right),
+            util::monospace_text(util::yen(sum))
+                .size(Self::RESULT_SIZE)
+                .width(Length::Fill)
+                .align_x(alignment::Horizontal::Right),
+        ]
+        .align_y(alignment::Vertical::Center)
+        .spacing(Self::SPACING);
+
+        column![
+            util::bold_text(format!("{} Overview (tax incl.)", self.year)),
+            canvas(year::Chart { totals })
+                .width(Length::Fill)
+                .height(Self::CHART_HEIGHT),
+            util::rounded_container(rows).padding(Self::SPACING),
+            year_total,
+        ]
+        .spacing(Self::SPACING)
+        .into()
+    }
+
+    fn title(&self) -> String {
+        self.title.clone()
+    }
+
+    fn setting<'a>(
+        label: &'a str,
+        content: impl Into<Element<'a, Message>>,
+    ) -> Element<'a, Message> {
+        use widget::{row, text};
+
+        row![
+            text(label).width(Self::SETTINGS_LABEL_WIDTH),
+            content.into()
+        ]
+        .align_y(alignment::Vertical::Center)
+        .spacing(Self::SPACING)
+        .into()
+    }
+
+    fn theme(&self) -> Theme {
+        self.settings.appearance.theme()
+    }
+
+    fn notifications(&self) -> Element<'_, Message> {
+        use widget::{button, column, container, row, scrollable, text};
+
+        let chain = |failure: &Failure| {
+            column(failure.chain().into_iter().enumerate().map(|(i, message)| {
+                if i == 0 {
+                    text(message).into()
+                } else {
+                    text(format!("caused by: {message}")).size(12).into()
+                }
+            }))
+        };
+
+        let toast = self.failures.front().filter(|_| self.toast_visible).map(
+            |LoggedFailure { failure, .. }| {
+                util::danger_container(
+                    row![
+                        chain(failure).width(Length::Fill),
+                        button("x").on_press(Message::ToastDismissed),
+                    ]
+                    .spacing(Self::SPACING),
+                )
+                .padding(Self::SPACING)
+            },
+        );
+
+        let log = self.log_visible.then(|| {
+            util::rounded_container(
+                column![
+                    row![
+                        util::bold_text("Errors").width(Length::Fill),
+                        button("Clear").on_press(Message::LogCleared),
+                    ]
+                    .align_y(alignment::Vertical::Center),
+                    scrollable(
+                        column(self.failures.iter().map(|LoggedFailure { time, failure }| {
+                            row![
+                                util::monospace_text(format!(
+                                    "{:02}:{:02}:{:02}",
+                                    time.hour(),
+                                    time.minute(),
+                                    time.second()
+                                )),
+                                chain(failure),
+                            ]
+                            .spacing(Self::SPACING)
+                            .into()
+                        }))
+                        .spacing(Self::SPACING)
+                    )
+                    .height(Self::LOG_HEIGHT),
+                ]
+                .spacing(Self::SPACING),
+            )
+            .padding(Self::SPACING)
+        });
+
+        let log_button = (!self.failures.is_empty()).then(|| {
+            button(text(format!("Errors ({})", self.failures.len())))
+                .style(button::secondary)
+                .on_press(Message::LogToggled)
+        });
+
+        container(
+            column![]
+                .push_maybe(log)
+                .push_maybe(toast)
+                .push_maybe(log_button)
+                .align_x(alignment::Horizontal::Right)
+                .spacing(Self::SPACING)
+                .width(Self::TOAST_WIDTH),
+        )
+        .padding(Self::PADDING)
         .width(Length::Fill)
+        .height(Length::Fill)
+        .align_right(Length::Fill)
+        .align_bottom(Length::Fill)
         .into()
     }
 
-    fn title(&self) -> String {
-        self.title.clone()
-    }
-
-    fn view(&self) -> Element<Message> {
-        use widget::{Space, button, column, pick_list, row, scrollable, text, text_input};
+    fn view(&self) -> Element<'_, Message> {
+        use widget::{
+            Space, button, checkbox, column, pick_list, progress_bar, row, scrollable, stack, text,
+            text_input, tooltip,
+        };
 
         let space = || Space::new(Self::SPACING, Self::SPACING);
 
+        let period: Element<Message> = match self.custom_range {
+            Some((start, end)) => row![
+                util::date_picker(start, Message::RangeStartPicked),
+                text("-"),
+                util::date_picker(end, Message::RangeEndPicked),
+            ]
+            .align_y(alignment::Vertical::Center)
+            .spacing(Self::SPACING)
+            .into(),
+            None => row![
+                pick_list(util::MONTHS, self.month_selected, Message::MonthSelected),
+                pick_list(
+                    offset::OFFSETS,
+                    self.offset_selected,
+                    Message::OffsetSelected
+                ),
+                button("-").on_press(Message::YearStepped(-1)),
+                util::monospace_text(self.year.to_string())
+                    .width(Self::YEAR_WIDTH)
+                    .align_x(alignment::Horizontal::Center),
+                button("+").on_press(Message::YearStepped(1)),
+            ]
+            .align_y(alignment::Vertical::Center)
+            .spacing(Self::SPACING)
+            .into(),
+        };
+
         let month_offset_year = row![
-            pick_list(util::MONTHS, self.month_selected, Message::MonthSelected),
-            text_input("Offset", &self.offset_input)
-                .width(Self::OFFSET_WIDTH)
-                .on_input(Message::OffsetInput),
-            text_input("Year", &self.year_input)
-                .width(Self::YEAR_WIDTH)
-                .on_input(Message::YearInput),
+            button("<").on_press(Message::PreviousPressed),
+            period,
+            button(">").on_press(Message::NextPressed),
+            button("Today").on_press(Message::TodayPressed),
+            button(if self.year_view { "Calendar" } else { "Year" })
+                .style(button::secondary)
+                .on_press(Message::YearViewToggled),
         ]
+        .align_y(alignment::Vertical::Center)
         .spacing(Self::SPACING);
 
+        let custom_range = checkbox("Custom range", self.custom_range.is_some())
+            .on_toggle(Message::CustomRangeToggled);
+
         let duration_input = match self.type_selected {
             Some(TypeForPickList::PerHour) => row![
                 text_input("Hour", &self.hour_input).on_input(Message::HourInput),
@@ -351,15 +1698,239 @@ impl App {
                 .on_input(Message::FilenameInput),
             button("Save").on_press(Message::SavePressed),
             pick_list(
-                self.find_jsons().unwrap_or_default(),
+                self.saves.as_slice(),
                 self.filename_selected.as_ref(),
                 Message::FilenameSelected
             ),
             button("Load").on_press(Message::LoadPressed),
+            button("Merge").on_press(Message::MergePressed),
             button("Delete").on_press(Message::RemoveFilePressed),
         ]
         .spacing(Self::SPACING);
 
+        let confirm = self.confirm.as_ref().map(|confirm| {
+            let question = match confirm {
+                Confirm::Overwrite(filename) => format!("Overwrite \"{filename}\"?"),
+                Confirm::Delete(filename) => format!("Delete \"{filename}\"?"),
+            };
+
+            row![
+                text(question).width(Length::Fill),
+                button("Yes")
+                    .style(button::danger)
+                    .on_press(Message::ConfirmPressed),
+                button("No").on_press(Message::CancelPressed),
+            ]
+            .align_y(alignment::Vertical::Center)
+            .spacing(Self::SPACING)
+        });
+
+        let describe = |config: &Config| {
+            format!(
+                "{} {}",
+                config.pay_to_string(),
+                config.r#type.duration_to_string()
+            )
+        };
+
+        let merge = self.merge.as_ref().map(|merge| {
+            let added = merge.added.iter().map(|(name, config)| {
+                row![
+                    text(format!("Add \"{name}\"")).width(Length::Fill),
+                    util::monospace_text(describe(config)),
+                ]
+                .spacing(Self::SPACING)
+                .into()
+            });
+
+            let conflicts = merge.conflicts.iter().enumerate().map(|(i, conflict)| {
+                let renamed = (conflict.resolution == Resolution::Rename)
+                    .then(|| text(format!("Theirs becomes \"{}\"", conflict.renamed)).size(12));
+
+                column![
+                    row![
+                        text(conflict.name.as_str()).width(Length::Fill),
+                        util::monospace_text(describe(&conflict.mine)),
+                        text("/"),
+                        util::monospace_text(describe(&conflict.theirs)),
+                        pick_list(merge::RESOLUTIONS, Some(conflict.resolution), move |x| {
+                            Message::MergeResolutionSelected(i, x)
+                        }),
+                    ]
+                    .align_y(alignment::Vertical::Center)
+                    .spacing(Self::SPACING),
+                ]
+                .push_maybe(renamed)
+                .into()
+            });
+
+            let unchanged = (merge.unchanged > 0)
+                .then(|| text(format!("{} identical configs are skipped", merge.unchanged)));
+            let nothing = merge.is_empty().then(|| text("Nothing to merge"));
+
+            util::rounded_container(
+                column![util::bold_text(format!(
+                    "Merge from \"{}\"",
+                    merge.filename
+                ))]
+                .extend(added)
+                .extend(conflicts)
+                .push_maybe(unchanged)
+                .push_maybe(nothing)
+                .push(
+                    row![
+                        button("Apply")
+                            .on_press_maybe((!merge.is_empty()).then_some(Message::MergeApplied)),
+                        button("Cancel")
+                            .style(button::secondary)
+                            .on_press(Message::MergeCancelled),
+                    ]
+                    .spacing(Self::SPACING),
+                )
+                .spacing(Self::SPACING),
+            )
+            .padding(Self::SPACING)
+        });
+
+        let configs_io = column![configs_io]
+            .push_maybe(confirm)
+            .push_maybe(merge)
+            .spacing(Self::SPACING);
+
+        let backups = self.backups.as_ref().map(|backups| {
+            let body: Element<Message> = if backups.is_empty() {
+                text("No backups yet").into()
+            } else {
+                column(backups.iter().enumerate().map(|(i, backup)| {
+                    row![
+                        text(backup.name.as_str()).width(Length::Fill),
+                        util::monospace_text(backup.taken()),
+                        util::monospace_text(match backup.config_count {
+                            Some(count) => format!("{count: >3} configs"),
+                            None => "unreadable".to_string(),
+                        }),
+                        button("Restore").on_press(Message::BackupRestorePressed(i)),
+                    ]
+                    .align_y(alignment::Vertical::Center)
+                    .spacing(Self::SPACING)
+                    .into()
+                }))
+                .spacing(Self::SPACING)
+                .into()
+            };
+
+            util::rounded_container(body).padding(Self::SPACING)
+        });
+
+        #[cfg(feature = "sqlite")]
+        let backend = Some(Self::setting(
+            "Storage",
+            row![pick_list(
+                settings::BACKENDS,
+                Some(self.settings.backend),
+                Message::BackendSelected
+            )]
+            .push_maybe(
+                (self.settings.backend == settings::Backend::Sqlite)
+                    .then(|| button("Import JSON Saves").on_press(Message::ImportPressed)),
+            )
+            .spacing(Self::SPACING),
+        ));
+        #[cfg(not(feature = "sqlite"))]
+        let backend: Option<Element<Message>> = None;
+
+        let settings_panel = self.settings_visible.then(|| {
+            util::rounded_container(
+                column![Self::setting(
+                    "Week",
+                    pick_list(
+                        settings::WEEK_STARTS,
+                        Some(self.settings.week_start),
+                        Message::WeekStartSelected
+                    )
+                )]
+                .push_maybe(self.local_offset.is_none().then(|| {
+                    Self::setting(
+                        "UTC offset",
+                        text_input("+09:00", &self.utc_offset_input)
+                            .width(Self::UTC_OFFSET_WIDTH)
+                            .on_input(Message::UtcOffsetInput)
+                            .on_submit(Message::UtcOffsetSubmitted),
+                    )
+                }))
+                .push(Self::setting(
+                    "Data folder",
+                    text_input(
+                        &storage::default_data_dir().display().to_string(),
+                        &self.data_dir_input,
+                    )
+                    .on_input(Message::DataDirInput)
+                    .on_submit(Message::DataDirSubmitted),
+                ))
+                .push(Self::setting(
+                    "Backups kept",
+                    row![
+                        text_input("Kept", &self.backup_count_input)
+                            .width(Self::BACKUP_COUNT_WIDTH)
+                            .on_input(Message::BackupCountInput)
+                            .on_submit(Message::BackupCountSubmitted),
+                        button("Backups").on_press(Message::BackupsToggled),
+                    ]
+                    .spacing(Self::SPACING),
+                ))
+                .push_maybe(backups)
+                .push_maybe(backend)
+                .push(Self::setting(
+                    "Theme",
+                    pick_list(
+                        settings::APPEARANCES,
+                        Some(self.settings.appearance),
+                        Message::AppearanceSelected,
+                    ),
+                ))
+                .push(Self::setting(
+                    "Start at",
+                    pick_list(
+                        settings::DEFAULT_PERIODS,
+                        Some(self.settings.default_period),
+                        Message::DefaultPeriodSelected,
+                    ),
+                ))
+                .push(Self::setting(
+                    "Per-time lasts",
+                    text_input("1:00", &self.per_time_input)
+                        .width(Self::DURATION_WIDTH)
+                        .on_input(Message::PerTimeInput)
+                        .on_submit(Message::PerTimeSubmitted),
+                ))
+                .push(Self::setting(
+                    "Default goal",
+                    text_input("None", &self.default_goal_input)
+                        .width(Self::GOAL_WIDTH)
+                        .on_input(Message::DefaultGoalInput)
+                        .on_submit(Message::DefaultGoalSubmitted),
+                ))
+                .push(Self::setting(
+                    "Default type",
+                    pick_list(
+                        config::TYPES_FOR_PICK_LIST,
+                        Some(self.settings.default_type),
+                        Message::DefaultTypeSelected,
+                    ),
+                ))
+                .spacing(Self::SPACING),
+            )
+            .padding(Self::SPACING)
+        });
+
+        let configurations_top = row![
+            util::bold_text("Configurations").width(Length::Fill),
+            button("Settings")
+                .style(button::secondary)
+                .on_press(Message::SettingsToggled),
+        ]
+        .align_y(alignment::Vertical::Center);
+
         let configs_input = row![
             text_input("Name", &self.name_input)
                 .width(Self::NAME_WIDTH)
@@ -367,6 +1938,14 @@ impl App {
             text_input("Pay", &self.pay_input)
                 .width(Self::PAY_WIDTH)
                 .on_input(Message::PayInput),
+            pick_list(
+                config::TAX_BASES,
+                Some(self.tax_basis_selected),
+                Message::TaxBasisSelected
+            ),
+            text_input("Tax %", &self.tax_rate_input)
+                .width(Self::TAX_RATE_WIDTH)
+                .on_input(Message::TaxRateInput),
             pick_list(
                 config::TYPES_FOR_PICK_LIST,
                 self.type_selected,
@@ -386,13 +1965,18 @@ impl App {
                     .align_x(alignment::Horizontal::Center)
             };
 
-            row![
-                top("Name", Self::NAME_WIDTH),
-                top("Pay", Self::PAY_WIDTH),
-                top("Duration", Self::DURATION_WIDTH),
-                top("#", Self::COUNT_WIDTH),
-                top("Sum", Self::SUM_WIDTH),
-            ]
+            let widths = [
+                Self::NAME_WIDTH,
+                Self::PAY_WIDTH,
+                Self::DURATION_WIDTH,
+                Self::COUNT_WIDTH,
+                Self::SUM_WIDTH,
+            ];
+
+            row(summary::HEADER
+                .into_iter()
+                .zip(widths)
+                .map(|(name, width)| top(name, width).into()))
             .spacing(Self::SPACING)
             .some()
         };
@@ -401,8 +1985,6 @@ impl App {
             .push_maybe(configs_top)
             .spacing(Self::SPACING);
 
-        let mut sum = 0;
-
         let configs_body = column(self.configs.iter().map(|(name, config)| {
             let count = self
                 .cells
@@ -410,8 +1992,6 @@ impl App {
                 .filter(|cell| cell.contains(name.as_str()))
                 .count();
 
-            sum += config.sum(count);
-
             row![
                 util::colored_button(
                     text(name)
@@ -422,9 +2002,13 @@ impl App {
                 .width(Self::NAME_WIDTH)
                 .padding(0)
                 .on_press(Message::AddPressed(name.to_owned())),
-                util::monospace_text(config.pay_to_string())
-                    .width(Self::PAY_WIDTH)
-                    .align_x(alignment::Horizontal::Right),
+                tooltip(
+                    util::monospace_text(config.pay_to_string())
+                        .width(Self::PAY_WIDTH)
+                        .align_x(alignment::Horizontal::Right),
+                    util::rounded_container(text(config.tax.to_string())).padding(Self::SPACING),
+                    tooltip::Position::Top,
+                ),
                 util::monospace_text(config.r#type.duration_to_string())
                     .width(Self::DURATION_WIDTH)
                     .align_x(alignment::Horizontal::Center),
@@ -442,9 +2026,141 @@ impl App {
         }))
         .spacing(Self::SPACING);
 
-        let result_body = util::monospace_text(util::yen(sum)).size(Self::RESULT_SIZE);
+        let statistics = Statistics::new(
+            &self.configs,
+            &self.cells,
+            self.settings.per_time_minutes as u32,
+        );
+
+        let totals = self.tax_totals();
+        let result_body = util::monospace_text(util::yen(totals.total())).size(Self::RESULT_SIZE);
+
+        let statistic = |label, value: Option<String>| {
+            row![
+                text(label).width(Length::Fill),
+                util::monospace_text(value.unwrap_or_else(|| "-".to_string())),
+            ]
+            .spacing(Self::SPACING)
+        };
+
+        let difference = if statistics.confirmed < statistics.sum {
+            format!("-{}", util::yen(statistics.sum - statistics.confirmed))
+        } else {
+            util::yen(statistics.confirmed - statistics.sum)
+        };
+
+        let tax_body = util::rounded_container(
+            column(
+                totals
+                    .breakdown()
+                    .map(|(label, amount)| statistic(label, util::yen(amount).some()).into()),
+            )
+            .spacing(Self::SPACING),
+        )
+        .padding(Self::SPACING);
+
+        let statistics_body = util::rounded_container(
+            column![
+                text("Tax included, like the total above").size(12),
+                statistic("Planned", util::yen(statistics.sum).some()),
+                statistic("Confirmed", util::yen(statistics.confirmed).some()),
+                statistic("Difference", difference.some()),
+                statistic("Paid hours", util::hours(statistics.paid_minutes).some()),
+                statistic("Days worked", statistics.days_worked.to_string().some()),
+                statistic("Per working day", statistics.per_day().map(util::yen)),
+                statistic(
+                    "Effective hourly rate",
+                    statistics.hourly_rate().map(util::yen)
+                ),
+            ]
+            .spacing(Self::SPACING),
+        )
+        .padding(Self::SPACING);
+
+        let goal_input = row![
+            text("Goal").width(Length::Fill),
+            text_input(
+                &self
+                    .settings
+                    .goal
+                    .map_or("None".to_string(), |x| format!("{x} (default)")),
+                &self.goal_input
+            )
+            .width(Self::GOAL_WIDTH)
+            .on_input(Message::GoalInput)
+            .on_submit(Message::GoalSubmitted),
+        ]
+        .align_y(alignment::Vertical::Center)
+        .spacing(Self::SPACING);
+
+        let goal_progress = self.goal().filter(|&x| x > 0).map(|goal| {
+            let remaining = goal.saturating_sub(statistics.sum);
+            let shifts = (remaining > 0).then(|| {
+                column(self.configs.iter().map(|(name, config)| {
+                    let per_shift = config.totals(1).total();
+                    let needed = remaining.div_ceil(per_shift.max(1)) as usize;
+                    let free_days = self.free_days(name);
+                    let verdict = if per_shift == 0 {
+                        "pays nothing".to_string()
+                    } else if needed <= free_days {
+                        format!("{needed} more")
+                    } else {
+                        format!("{needed} more (only {free_days} days left)")
+                    };
+
+                    row![
+                        text(name.as_str()).width(Length::Fill),
+                        util::monospace_text(verdict),
+                    ]
+                    .spacing(Self::SPACING)
+                    .into()
+                }))
+                .spacing(Self::SPACING)
+            });
+
+            column![
+                progress_bar(0.0..=goal as f32, statistics.sum.min(goal) as f32),
+                text(if remaining > 0 {
+                    format!(
+                        "{}% of {}, {} to go",
+                        statistics.sum as u64 * 100 / goal as u64,
+                        util::yen(goal),
+                        util::yen(remaining)
+                    )
+                } else {
+                    format!("Reached {}", util::yen(goal))
+                }),
+            ]
+            .push_maybe(shifts)
+            .spacing(Self::SPACING)
+        });
+
+        let goal = util::rounded_container(
+            column![goal_input]
+                .push_maybe(goal_progress)
+                .spacing(Self::SPACING),
+        )
+        .padding(Self::SPACING);
 
-        let calendar_top = row(util::WEEKDAYS.map(|weekday| {
+        let selection_button = |label, message| {
+            button(
+                text(label)
+                    .width(Length::Fill)
+                    .align_x(alignment::Horizontal::Center),
+            )
+            .width(Length::Fill)
+            .on_press(message)
+        };
+
+        let selection_buttons = row![
+            selection_button("Deselect All", Message::DeselectPressed),
+            selection_button("Invert Selection", Message::InvertPressed),
+            selection_button("Select All in Period", Message::SelectAllPressed),
+            selection_button("Confirm Up to Today", Message::ConfirmUpToTodayPressed),
+        ]
+        .spacing(Self::SPACING);
+
+        let calendar_top = row(util::weekdays(self.week_start()).map(|weekday| {
             util::colored_button(
                 text(util::short_weekday(weekday).to_string())
                     .width(Length::Fill)
@@ -457,70 +2173,319 @@ impl App {
         }))
         .spacing(Self::SPACING);
 
-        let calendar_body = column((0..Self::CALENDAR_ROWS).map(|r| {
+        let calendar_body = column((0..self.calendar_rows()).map(|r| {
             row((0..Self::CALENDAR_COLUMNS).map(|c| self.calendar_cell(r, c)))
                 .spacing(Self::SPACING)
                 .into()
         }))
         .spacing(Self::CALENDER_VERTICAL_SPACING);
 
-        row![
-            scrollable(
-                column![
-                    util::bold_text("Date"),
-                    month_offset_year,
-                    space(),
-                    util::bold_text("Calendar"),
-                    button(
-                        text("Deselect All")
-                            .width(Length::Fill)
-                            .align_x(alignment::Horizontal::Center)
-                    )
-                    .on_press(Message::DeselectPressed),
-                    calendar_top,
-                    calendar_body,
+        let recovery = self.recovered.as_ref().map(|_| {
+            util::rounded_container(
+                row![
+                    text("The previous session did not exit cleanly.").width(Length::Fill),
+                    button("Restore").on_press(Message::RestorePressed),
+                    button("Discard")
+                        .style(button::secondary)
+                        .on_press(Message::DiscardPressed),
                 ]
-                .padding(Self::PADDING)
-                .spacing(Self::SPACING)
-            ),
+                .align_y(alignment::Vertical::Center)
+                .spacing(Self::SPACING),
+            )
+            .padding(Self::SPACING)
+        });
+
+        let left = column![]
+            .push_maybe(recovery)
+            .push(util::bold_text("Date"))
+            .push(month_offset_year)
+            .push(custom_range)
+            .push(space());
+
+        let left = if self.year_view {
+            left.push(self.year_overview())
+        } else {
+            left.push(util::bold_text("Calendar"))
+                .push(selection_buttons)
+                .push(calendar_top)
+                .push(calendar_body)
+        };
+
+        #[cfg(feature = "png")]
+        let image_button = Some(
+            button("Export Image")
+                .style(button::secondary)
+                .on_press_maybe(self.cells_period.map(|_| Message::ImagePressed)),
+        );
+        #[cfg(not(feature = "png"))]
+        let image_button: Option<Element<Message>> = None;
+
+        let main = row![
+            scrollable(left.padding(Self::PADDING).spacing(Self::SPACING)),
             scrollable(
-                column![
-                    util::bold_text("Configurations"),
-                    configs_io,
-                    configs_input_and_top,
-                    configs_body,
-                    space(),
-                    util::bold_text("Result"),
-                    result_body,
-                ]
-                .padding(Self::PADDING)
-                .spacing(Self::SPACING)
-                .width(Self::RIGHT_WIDTH)
+                column![configurations_top]
+                    .push_maybe(settings_panel)
+                    .push(configs_io)
+                    .push(configs_input_and_top)
+                    .push(configs_body)
+                    .push(space())
+                    .push(
+                        row![
+                            util::bold_text("Result").width(Length::Fill),
+                            pick_list(
+                                summary::FORMATS,
+                                Some(self.settings.summary_format),
+                                Message::SummaryFormatSelected
+                            ),
+                            button("Copy Summary")
+                                .style(button::secondary)
+                                .on_press(Message::CopySummaryPressed),
+                            button("Export Report")
+                                .style(button::secondary)
+                                .on_press_maybe(self.cells_period.map(|_| Message::ReportPressed)),
+                        ]
+                        .push_maybe(image_button)
+                        .align_y(alignment::Vertical::Center)
+                    )
+                    .push(result_body)
+                    .push(tax_body)
+                    .push(statistics_body)
+                    .push(goal)
+                    .push(self.payslip_view())
+                    .push(self.invoice_view())
+                    .padding(Self::PADDING)
+                    .spacing(Self::SPACING)
+                    .width(Self::RIGHT_WIDTH)
             ),
         ]
-        .spacing(Self::SPACING)
-        .into()
+        .spacing(Self::SPACING);
+
+        stack![main, self.notifications()].into()
     }
 
-    fn update(&mut self, message: Message) {
-        self.reset_title();
+    fn update(&mut self, message: Message) -> Task<Message> {
+        let ambient = message.is_ambient();
+
+        if !ambient {
+            self.reset_title();
+        }
 
         match message {
             Message::MonthSelected(month) => {
-                self.clear_cells();
-                self.month_selected = Some(month)
+                self.month_selected = Some(month);
+                self.reset_cells();
+            }
+            Message::WeekStartSelected(week_start) => {
+                self.settings.week_start = week_start;
+                self.reset_cells();
+
+                self.save_settings();
+            }
+            Message::SettingsToggled => self.settings_visible = !self.settings_visible,
+            Message::AppearanceSelected(appearance) => {
+                self.settings.appearance = appearance;
+                self.save_settings();
+            }
+            Message::DefaultPeriodSelected(default_period) => {
+                self.settings.default_period = default_period;
+                self.save_settings();
+            }
+            Message::DefaultTypeSelected(default_type) => {
+                self.settings.default_type = default_type;
+                self.save_settings();
+            }
+            Message::PerTimeInput(x) => self.per_time_input = x,
+            Message::PerTimeSubmitted => match util::parse_hour_minute(&self.per_time_input) {
+                Ok(hm) => {
+                    self.settings.per_time_minutes = hm.minutes();
+                    self.save_settings();
+                }
+                Err(failure) => self.set_failure(failure),
+            },
+            Message::GoalInput(x) => self.goal_input = x,
+            Message::GoalSubmitted => {
+                let goal = if self.goal_input.trim().is_empty() {
+                    Ok(None)
+                } else {
+                    self.goal_input.trim().parse().map(Some)
+                };
+
+                match goal {
+                    Ok(goal) => {
+                        if let Some(record) = self.current_record_mut() {
+                            record.goal = goal;
+                        }
+                    }
+                    Err(_) => self.set_failure(Failure::Goal),
+                }
+            }
+            Message::DefaultGoalInput(x) => self.default_goal_input = x,
+            Message::DefaultGoalSubmitted => {
+                let goal = if self.default_goal_input.trim().is_empty() {
+                    Ok(None)
+                } else {
+                    self.default_goal_input.trim().parse().map(Some)
+                };
+
+                match goal {
+                    Ok(goal) => {
+                        self.settings.goal = goal;
+                        self.save_settings();
+                    }
+                    Err(_) => self.set_failure(Failure::Goal),
+                }
+            }
+            Message::PayslipToggled => {
+                self.payslip = match self.payslip {
+                    Some(_) => None,
+                    None => Some(PayslipForm::new(
+                        &self.expected(),
+                        self.reconciliation().map(|x| &x.payslip),
+                    )),
+                }
+            }
+            Message::PayslipHoursInput(name, x) => {
+                if let Some(input) = self.payslip.as_mut().and_then(|f| f.input_mut(name)) {
+                    input.hours = x;
+                }
+            }
+            Message::PayslipAmountInput(name, x) => {
+                if let Some(input) = self.payslip.as_mut().and_then(|f| f.input_mut(name)) {
+                    input.amount = x;
+                }
+            }
+            Message::PayslipSaved => match self.payslip.as_ref().map(PayslipForm::parse) {
+                Some(Ok(payslip)) => {
+                    let reconciliation = Reconciliation {
+                        checked: self.today(),
+                        expected: self.expected(),
+                        payslip,
+                    };
+
+                    if let Some(record) = self.current_record_mut() {
+                        record.reconciliation = Some(reconciliation);
+                    }
+                    self.payslip = None;
+                }
+                Some(Err(failure)) => self.set_failure(failure),
+                None => (),
+            },
+            Message::ReportPressed => {
+                let written = self
+                    .report()
+                    .map(|x| document::write(&x, &self.reports_dir(), Failure::Report));
+
+                match written {
+                    Some(Ok((paths, skipped))) => {
+                        let paths: Vec<_> = paths.iter().map(|x| x.display().to_string()).collect();
+                        self.set_title(&paths.join(", "));
+
+                        if let Some(failure) = skipped {
+                            self.set_failure(failure);
+                        }
+                    }
+                    Some(Err(failure)) => self.set_failure(failure),
+                    None => (),
+                }
+            }
+            #[cfg(feature = "png")]
+            Message::ImagePressed => {
+                match self.report().map(|x| x.write_png(&self.reports_dir())) {
+                    Some(Ok(path)) => self.set_title(&path.display().to_string()),
+                    Some(Err(failure)) => self.set_failure(failure),
+                    None => (),
+                }
+            }
+            Message::InvoiceToggled => self.invoice_visible = !self.invoice_visible,
+            Message::InvoiceIssuerInput(x) => self.settings.invoice.issuer = x,
+            Message::InvoiceAddressInput(x) => self.settings.invoice.address = x,
+            Message::InvoiceRegistrationInput(x) => self.settings.invoice.registration_number = x,
+            Message::InvoiceClientInput(x) => self.settings.invoice.client = x,
+            Message::InvoiceNumberInput(x) => self.invoice_number_input = x,
+            Message::InvoiceIssued => {
+                let written = self.invoice().and_then(|x| {
+                    let next = x.number.checked_add(1).ok_or(Failure::InvoiceNumber)?;
+                    let dir = self.invoices_dir();
+
+                    // an issued invoice must not change afterwards
+                    if document::paths(&x, &dir).iter().any(|x| x.exists()) {
+                        return Err(Failure::InvoiceIssued(x.number_to_string()));
+                    }
+
+                    let paths = document::write(&x, &dir, Failure::Invoice)?;
+                    Ok((next, paths))
+                });
+
+                match written {
+                    Ok((next, (paths, skipped))) => {
+                        let paths: Vec<_> = paths.iter().map(|x| x.display().to_string()).collect();
+                        self.set_title(&paths.join(", "));
+
+                        if let Some(failure) = skipped {
+                            self.set_failure(failure);
+                        }
+
+                        self.settings.invoice.next_number = next;
+                        self.invoice_number_input = next.to_string();
+                        self.invoice_visible = false;
+                        self.save_settings();
+                    }
+                    Err(failure) => self.set_failure(failure),
+                }
+            }
+            Message::WindowResized(size) => {
+                self.settings.window_size = (size.width, size.height);
+                return Task::none();
+            }
+            Message::TodayPressed => self.jump_to_today(),
+            Message::YearViewToggled => self.year_view = !self.year_view,
+            Message::UtcOffsetInput(x) => self.utc_offset_input = x,
+            Message::UtcOffsetSubmitted => {
+                let offset = if self.utc_offset_input.trim().is_empty() {
+                    Ok(None)
+                } else {
+                    util::parse_utc_offset(&self.utc_offset_input)
+                        .map(Some)
+                        .ok_or(Failure::UtcOffset)
+                };
+
+                match offset {
+                    Ok(offset) => {
+                        self.settings.set_utc_offset(offset);
+
+                        self.save_settings();
+                    }
+                    Err(failure) => self.set_failure(failure),
+                }
+            }
+            Message::PreviousPressed => self.shift_period(-1),
+            Message::NextPressed => self.shift_period(1),
+            Message::CustomRangeToggled(enabled) => self.toggle_custom_range(enabled),
+            Message::RangeStartPicked(start) => {
+                if let Some((_, end)) = self.custom_range {
+                    self.custom_range = Some((start, end.max(start)));
+                    self.reset_cells();
+                }
             }
-            Message::OffsetInput(offset) => {
-                self.clear_cells();
-                self.offset_input = offset
+            Message::RangeEndPicked(end) => {
+                if let Some((start, _)) = self.custom_range {
+                    self.custom_range = Some((start.min(end), end));
+                    self.reset_cells();
+                }
             }
-            Message::YearInput(year) => {
-                self.clear_cells();
-                self.year_input = year
+            Message::OffsetSelected(offset) => {
+                self.offset_selected = Some(offset);
+                self.reset_cells();
+
+                self.settings.offset = offset;
+                self.save_settings();
             }
+            Message::YearStepped(n) => self.shift_period(12 * n as i64),
             Message::NameInput(name) => self.name_input = name,
             Message::TypeSelected(r#type) => self.type_selected = Some(r#type),
             Message::PayInput(pay) => self.pay_input = pay,
+            Message::TaxBasisSelected(basis) => self.tax_basis_selected = basis,
+            Message::TaxRateInput(rate) => self.tax_rate_input = rate,
             Message::PushPressed => match self.config() {
                 Ok(config) => {
                     self.configs.insert(self.name_input.clone(), config);
@@ -532,13 +2497,50 @@ impl App {
                 for cell in &mut self.cells {
                     cell.remove(&name);
                 }
+                for period in &mut self.periods {
+                    for (_, names) in &mut period.assignments {
+                        names.remove(&name);
+                    }
+                    period.assignments.retain(|(_, names)| !names.is_empty());
+                }
+                self.periods.retain(|x| {
+                    !x.assignments.is_empty() || x.reconciliation.is_some() || x.goal.is_some()
+                });
             }
-            Message::RemoveFilePressed => {
-                if let Err(failure) = self.remove_file() {
-                    self.set_failure(failure);
+            Message::RemoveFilePressed => match self.filename_selected.clone() {
+                Some(filename) => self.confirm = Some(Confirm::Delete(filename)),
+                None => self.set_failure(Failure::NoFileSelected),
+            },
+            Message::ConfirmPressed => {
+                match self.confirm.take() {
+                    Some(Confirm::Overwrite(filename)) => {
+                        if let Err(failure) = self.save(&filename) {
+                            self.set_failure(failure);
+                        }
+                    }
+                    Some(Confirm::Delete(filename)) => {
+                        match self.remove_file(&filename) {
+                            Ok(()) if self.settings.last_file == Some(filename) => {
+                                self.remember_file(None)
+                            }
+                            Ok(()) => (),
+                            Err(failure) => self.set_failure(failure),
+                        }
+
+                        self.filename_selected = None;
+                    }
+                    None => (),
                 }
 
-                self.filename_selected = None;
+                self.refresh_saves();
+                self.refresh_backups();
+            }
+            Message::CancelPressed => self.confirm = None,
+            Message::ToastDismissed => self.toast_visible = false,
+            Message::LogToggled => self.log_visible = !self.log_visible,
+            Message::LogCleared => {
+                self.failures.clear();
+                self.log_visible = false;
             }
             Message::HourInput(x) => self.hour_input = x,
             Message::MinuteInput(x) => self.minute_input = x,
@@ -547,61 +2549,325 @@ impl App {
                 .iter_mut()
                 .filter(|x| x.selected)
                 .for_each(|x| x.insert(name.clone())),
-            Message::CellChecked(b, i) => match self.get_cell_mut(i as usize) {
-                Ok(cell) => cell.selected = b,
-                Err(failure) => self.set_failure(failure),
+            Message::CellChecked(b, i) => match self.anchor {
+                Some(anchor) if self.modifiers.shift() => self.select_range(anchor, i, b),
+                _ => match self.get_cell_mut(i) {
+                    Ok(cell) => {
+                        cell.selected = b;
+                        self.anchor = Some(i);
+                    }
+                    Err(failure) => self.set_failure(failure),
+                },
             },
-            Message::CellButtonPressed(name, i) => match self.get_cell_mut(i as usize) {
+            Message::CellButtonPressed(name, i) => match self.get_cell_mut(i) {
                 Ok(cell) => cell.remove(&name),
                 Err(failure) => self.set_failure(failure),
             },
+            Message::CellButtonRightPressed(name, i) => match self.get_cell_mut(i) {
+                Ok(cell) => cell.toggle_status(&name),
+                Err(failure) => self.set_failure(failure),
+            },
+            Message::ConfirmUpToTodayPressed => self.confirm_up_to_today(),
             Message::DeselectPressed => self.deselect(),
+            Message::InvertPressed => self.invert_selection(),
+            Message::SelectAllPressed => self.select_all(),
+            Message::CellPressed(i) => match self.anchor {
+                Some(anchor) if self.modifiers.shift() => self.select_range(anchor, i, true),
+                _ => {
+                    self.drag = Some(Drag {
+                        origin: i,
+                        base: self.cells.iter().map(|cell| cell.selected).collect(),
+                    });
+                    self.select_range(i, i, true);
+                    self.anchor = Some(i);
+                }
+            },
+            Message::CellEntered(i) => {
+                if let Some(Drag { origin, base }) = self.drag.take() {
+                    for (cell, selected) in self.cells.iter_mut().zip(&base) {
+                        cell.selected = *selected;
+                    }
+                    self.select_range(origin, i, true);
+                    self.drag = Some(Drag { origin, base });
+                }
+            }
+            Message::MouseReleased => self.drag = None,
+            Message::ModifiersChanged(modifiers) => self.modifiers = modifiers,
+            Message::DataDirInput(x) => self.data_dir_input = x,
+            Message::BackupCountInput(x) => self.backup_count_input = x,
+            Message::BackupCountSubmitted => match self.backup_count_input.trim().parse() {
+                Ok(count) => {
+                    self.settings.backup_count = count;
+
+                    self.save_settings();
+                }
+                Err(_) => self.set_failure(Failure::BackupCount),
+            },
+            #[cfg(feature = "sqlite")]
+            Message::BackendSelected(backend) => {
+                self.settings.backend = backend;
+                self.filename_selected = None;
+                self.refresh_saves();
+                self.refresh_backups();
+
+                self.save_settings();
+            }
+            #[cfg(feature = "sqlite")]
+            Message::ImportPressed => {
+                let data_dir = self.settings.data_dir();
+                let imported = sqlite::import(
+                    &Checked(JsonStorage::new(&data_dir)),
+                    &Checked(sqlite::SqliteStorage::new(&data_dir)),
+                    self.now(),
+                    self.settings.backup_count,
+                );
+
+                match imported {
+                    Ok(count) => self.set_title(&format!("Imported {count} saves")),
+                    Err(failure) => self.set_failure(failure),
+                }
+
+                self.refresh_saves();
+            }
+            Message::BackupsToggled => {
+                self.backups = match self.backups {
+                    Some(_) => None,
+                    None => Some(Vec::new()),
+                };
+                self.refresh_backups();
+            }
+            Message::BackupRestorePressed(i) => {
+                let restored = self.backups.as_ref().and_then(|x| x.get(i)).map(|backup| {
+                    self.storage()
+                        .restore(backup, self.now(), self.settings.backup_count)
+                        .map(|()| backup.name.clone())
+                });
+
+                match restored {
+                    Some(Ok(name)) => {
+                        self.set_title(&format!("Restored \"{name}\" from backup"));
+                        self.filename_selected = Some(name);
+                    }
+                    Some(Err(failure)) => self.set_failure(failure),
+                    None => (),
+                }
+
+                self.refresh_saves();
+                self.refresh_backups();
+            }
+            Message::DataDirSubmitted => {
+                // the recovery file moves along with the data directory
+                if let Err(failure) = self.recovery().remove() {
+                    self.set_failure(failure);
+                }
+                self.last_autosave.clear();
+
+                let data_dir = self.data_dir_input.trim();
+
+                self.settings.data_dir = if data_dir.is_empty() {
+                    None
+                } else {
+                    Some(PathBuf::from(data_dir))
+                };
+                self.filename_selected = None;
+                self.refresh_saves();
+
+                self.save_settings();
+            }
             Message::FilenameInput(filename) => self.filename_input = filename,
             Message::FilenameSelected(filename) => self.filename_selected = Some(filename),
             Message::LoadPressed => match self.load() {
-                Ok(configs) => {
-                    self.clear_added();
-                    self.configs = configs;
+                Ok(data) => {
+                    self.open(data);
+                    self.remember_file(self.filename_selected.clone().as_deref());
                 }
                 Err(failure) => self.set_failure(failure),
             },
-            Message::SavePressed => match self.save() {
-                Ok(()) => self.set_title("Configurations saved"),
+            Message::MergePressed => match self.load() {
+                Ok(SaveData { configs, .. }) => {
+                    let filename = self.filename_selected.clone().unwrap_or_default();
+                    self.merge = Some(Merge::new(filename, &self.configs, configs));
+                }
                 Err(failure) => self.set_failure(failure),
             },
+            Message::MergeResolutionSelected(i, resolution) => {
+                if let Some(conflict) = self.merge.as_mut().and_then(|x| x.conflicts.get_mut(i)) {
+                    conflict.resolution = resolution;
+                }
+            }
+            Message::MergeApplied => {
+                if let Some(merge) = self.merge.take() {
+                    let filename = merge.filename.clone();
+
+                    merge.apply(&mut self.configs);
+                    self.set_title(&format!("Merged configurations from \"{filename}\""));
+                }
+            }
+            Message::MergeCancelled => self.merge = None,
+            Message::SavePressed => {
+                if let Err(failure) = self.request_save() {
+                    self.set_failure(failure);
+                }
+            }
             Message::WeekdayPressed(weekday) => {
                 for i in 0..self.cells.len() {
-                    let highlighted = self
-                        .cell_date(i)
-                        .map(|date| self.is_highlighted(&date))
-                        .unwrap_or(false);
-                    let column = i as u8 % Self::CALENDAR_COLUMNS;
-                    let select = highlighted && (column == util::weekday_to_column(weekday));
+                    let highlighted = self.is_cell_highlighted(i);
+                    let column = i % Self::CALENDAR_COLUMNS;
+                    let select = highlighted
+                        && (column == util::weekday_to_column(weekday, self.week_start()) as usize);
                     if select {
                         self.get_cell_mut(i).expect("unreachable").select();
                     }
                 }
             }
+            Message::RestorePressed => {
+                if let Some(snapshot) = self.recovered.take() {
+                    self.restore(snapshot);
+                }
+            }
+            Message::DiscardPressed => self.recovered = None,
+            Message::SummaryFormatSelected(format) => {
+                self.settings.summary_format = format;
+                self.save_settings();
+            }
+            Message::CopySummaryPressed => {
+                let summary =
+                    summary::summary(&self.configs, &self.cells, self.settings.summary_format);
+                return iced::clipboard::write(summary);
+            }
+            Message::SavesPolled => {
+                self.refresh_saves();
+                return Task::none();
+            }
+            Message::AutosaveTick => {
+                self.autosave(true);
+                return Task::none();
+            }
+            Message::CloseRequested(id) => {
+                // the window is going away, so failures can only go to stderr
+                // and must not keep it open
+                let failures = [
+                    (!self.settings_unreadable)
+                        .then(|| self.settings.save().err())
+                        .flatten(),
+                    // a clean exit leaves no recovery file behind
+                    self.recovered
+                        .is_none()
+                        .then(|| self.recovery().remove().err())
+                        .flatten(),
+                ];
+
+                for failure in failures.into_iter().flatten() {
+                    eprintln!("{}", failure.chain().join(": "));
+                }
+
+                return window::close(id);
+            }
+        }
+
+        // hovering alone changes nothing worth writing
+        if !ambient {
+            self.autosave(false);
         }
+
+        Task::none()
+    }
+
+    fn subscription(&self) -> Subscription<Message> {
+        Subscription::batch([
+            event::listen_with(|event, _, _| match event {
+                Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
+                    Message::ModifiersChanged(modifiers).some()
+                }
+                Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                    Message::MouseReleased.some()
+                }
+                Event::Window(window::Event::Resized(size)) => Message::WindowResized(size).some(),
+                _ => None,
+            }),
+            iced::time::every(Self::AUTOSAVE_INTERVAL).map(|_| Message::AutosaveTick),
+            iced::time::every(Self::SAVES_POLL_INTERVAL).map(|_| Message::SavesPolled),
+            window::close_requests().map(Message::CloseRequested),
+        ])
     }
 }
 
+/// The windows subsystem starts without a console, so output of the
+/// command-line modes would be lost. They write to the one they were run
+/// from instead, if any.
+#[cfg(windows)]
+fn attach_console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+
+    // SAFETY: takes no pointers, and failing only means there is no console
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+/// For the command-line modes, which have nowhere else to report to.
+#[cfg(any(feature = "png", feature = "sqlite"))]
+fn or_exit<T>(result: Result<T, Failure>) -> T {
+    result.unwrap_or_else(|failure| {
+        eprintln!("{}", failure.chain().join(": "));
+        std::process::exit(1)
+    })
+}
+
 fn main() -> iced::Result {
-    const WINDOW_SIZE: Size = Size {
-        width: 1550.0,
-        height: 800.0,
-    };
-
-    let palette = theme::Palette {
-        background: Color::from_rgb8(31, 31, 31),
-        text: Color::from_rgb8(0xFF, 0xFF, 0xFF),
-        primary: Color::from_rgb8(0, 0x3F, 0x7F),
-        danger: Color::from_rgb8(0xFF, 0, 0),
-        success: Color::from_rgb8(0, 0xFF, 0),
-    };
+    // must be queried before iced spawns any threads
+    let local_offset = UtcOffset::current_local_offset().ok();
+
+    #[cfg(windows)]
+    if std::env::args().nth(1).is_some_and(|x| x.starts_with("--")) {
+        attach_console();
+    }
+
+    #[cfg(feature = "sqlite")]
+    if std::env::args().nth(1).as_deref() == Some("--import-json") {
+        let settings = or_exit(Settings::load());
+        let data_dir = settings.data_dir();
+        let now = OffsetDateTime::now_utc().to_offset(
+            local_offset
+                .or(settings.utc_offset())
+                .unwrap_or(UtcOffset::UTC),
+        );
+        let imported = sqlite::import(
+            &Checked(JsonStorage::new(&data_dir)),
+            &Checked(sqlite::SqliteStorage::new(&data_dir)),
+            now,
+            settings.backup_count,
+        );
+
+        println!("Imported {} saves", or_exit(imported));
+        return Ok(());
+    }
+
+    // for sharing the schedule without opening a window
+    #[cfg(feature = "png")]
+    if std::env::args().nth(1).as_deref() == Some("--export-png") {
+        // defaults could point at another data folder
+        let app = App::new(local_offset, Ok(or_exit(Settings::load())));
+        let path = or_exit(app.export_png(std::env::args().nth(2)));
+
+        println!("{}", path.display());
+        return Ok(());
+    }
+
+    let settings = Settings::load();
+    let window_size = settings
+        .as_ref()
+        .map_or_else(|_| Settings::default().window_size(), Settings::window_size);
 
     iced::application(App::title, App::update, App::view)
-        .theme(move |_| Theme::custom("Custom".to_string(), palette))
-        .window_size(WINDOW_SIZE)
-        .run()
+        .subscription(App::subscription)
+        .theme(App::theme)
+        .window_size(window_size)
+        .exit_on_close_request(false)
+        .run_with(move || (App::new(local_offset, settings), Task::none()))
 }
@@ -0,0 +1,108 @@
+use crate::{config::Config, storage::Configs};
+use std::{
+    collections::BTreeSet,
+    fmt::{Display, Formatter},
+};
+
+/// What to do with an incoming config whose name is taken by a different one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Resolution {
+    KeepMine,
+    TakeTheirs,
+    Rename,
+}
+
+pub const RESOLUTIONS: [Resolution; 3] = [
+    Resolution::KeepMine,
+    Resolution::TakeTheirs,
+    Resolution::Rename,
+];
+
+pub struct Conflict {
+    pub name: String,
+    pub mine: Config,
+    pub theirs: Config,
+    /// Free of clashes with both sides, worked out up front so that the
+    /// preview shows what the config will be called.
+    pub renamed: String,
+    pub resolution: Resolution,
+}
+
+/// Configs of another save, sorted by what merging them would do.
+pub struct Merge {
+    pub filename: String,
+    pub added: Configs,
+    pub conflicts: Vec<Conflict>,
+    /// Same name, same pay and type.
+    pub unchanged: usize,
+}
+
+impl Merge {
+    pub fn new(filename: String, mine: &Configs, theirs: Configs) -> Self {
+        let mut taken: BTreeSet<String> = mine.keys().chain(theirs.keys()).cloned().collect();
+        let mut added = Configs::new();
+        let mut conflicts = Vec::new();
+        let mut unchanged = 0;
+
+        for (name, config) in theirs {
+            match mine.get(&name) {
+                None => {
+                    added.insert(name, config);
+                }
+                Some(existing) if existing == &config => unchanged += 1,
+                Some(existing) => {
+                    let renamed = (2..)
+                        .map(|i| format!("{name} ({i})"))
+                        .find(|x| !taken.contains(x))
+                        .expect("unreachable because the names are finite");
+
+                    taken.insert(renamed.clone());
+                    conflicts.push(Conflict {
+                        mine: existing.clone(),
+                        theirs: config,
+                        name,
+                        renamed,
+                        resolution: Resolution::KeepMine,
+                    });
+                }
+            }
+        }
+
+        Self {
+            filename,
+            added,
+            conflicts,
+            unchanged,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.conflicts.is_empty()
+    }
+
+    pub fn apply(self, configs: &mut Configs) {
+        configs.extend(self.added);
+
+        for conflict in self.conflicts {
+            match conflict.resolution {
+                Resolution::KeepMine => (),
+                Resolution::TakeTheirs => {
+                    configs.insert(conflict.name, conflict.theirs);
+                }
+                Resolution::Rename => {
+                    configs.insert(conflict.renamed, conflict.theirs);
+                }
+            }
+        }
+    }
+}
+
+impl Display for Resolution {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::KeepMine => write!(f, "Keep mine"),
+            Self::TakeTheirs => write!(f, "Take theirs"),
+            Self::Rename => write!(f, "Rename"),
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use time::Month;
+
+/// First day of a monthly period. A period runs up to (excluding) the same
+/// offset in the following month.
+///
+/// Days that a short month lacks are clamped to its last day, so `Day(30)`
+/// starts on Feb 28 (or 29) and consecutive periods still tile without gaps.
+/// `EndOfMonth` always starts on the last day of the month.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Offset {
+    Day(u8),
+    EndOfMonth,
+}
+
+pub const OFFSETS: [Offset; 31] = {
+    let mut retval = [Offset::EndOfMonth; 31];
+    let mut i = 0;
+
+    while i < 30 {
+        retval[i] = Offset::Day(i as u8 + 1);
+        i += 1;
+    }
+
+    retval
+};
+
+impl Offset {
+    pub const fn day(&self, year: i32, month: Month) -> u8 {
+        let length = month.length(year);
+
+        match self {
+            Self::Day(day) if *day < length => *day,
+            _ => length,
+        }
+    }
+}
+
+impl Display for Offset {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Day(day) => write!(f, "{day}"),
+            Self::EndOfMonth => write!(f, "End"),
+        }
+    }
+}
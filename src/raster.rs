@@ -0,0 +1,205 @@
+use crate::{failure::Failure, fonts};
+use ab_glyph::{Font, FontVec, PxScale, ScaleFont, point};
+use iced::{Color, alignment::Horizontal};
+use std::collections::HashSet;
+use tiny_skia::{Paint, PathBuilder, Pixmap, PremultipliedColorU8, Rect, Stroke, Transform};
+
+/// System fonts, loaded as needed. Characters the sans-serif face lacks
+/// (Japanese names, for one) are looked up in [`fonts::fallbacks`].
+struct Fonts {
+    database: fontdb::Database,
+    primary: FontVec,
+    fallbacks: Vec<FontVec>,
+    /// Fallback faces not loaded yet, next one last.
+    pending: Vec<fontdb::ID>,
+    /// Characters no face has, so they are not searched for again.
+    missing: HashSet<char>,
+}
+
+impl Fonts {
+    fn load(database: &fontdb::Database, id: fontdb::ID) -> Option<FontVec> {
+        database
+            .with_face_data(id, |data, index| {
+                FontVec::try_from_vec_and_index(data.to_vec(), index).ok()
+            })
+            .flatten()
+    }
+
+    fn new() -> Result<Self, Failure> {
+        let database = fonts::system();
+
+        let query = fontdb::Query {
+            families: &[fontdb::Family::SansSerif],
+            ..Default::default()
+        };
+        let regular = |x: &&fontdb::FaceInfo| {
+            !x.monospaced && x.style == fontdb::Style::Normal && x.weight == fontdb::Weight::NORMAL
+        };
+        let sans = |x: &&fontdb::FaceInfo| x.families.iter().any(|(name, _)| name.contains("Sans"));
+        // the generic family is often not configured, so guess from the names
+        let primary_id = database
+            .query(&query)
+            .or_else(|| database.faces().filter(regular).find(sans).map(|x| x.id))
+            .or_else(|| database.faces().find(regular).map(|x| x.id))
+            .ok_or(Failure::Font)?;
+        let primary = Self::load(&database, primary_id).ok_or(Failure::Font)?;
+        let mut pending = fonts::fallbacks(&database);
+        pending.retain(|&x| x != primary_id);
+        pending.reverse();
+
+        Ok(Self {
+            database,
+            primary,
+            fallbacks: Vec::new(),
+            pending,
+            missing: HashSet::new(),
+        })
+    }
+
+    fn has(font: &FontVec, c: char) -> bool {
+        font.glyph_id(c).0 != 0
+    }
+
+    fn font_for(&mut self, c: char) -> &FontVec {
+        if Self::has(&self.primary, c) || c.is_whitespace() || self.missing.contains(&c) {
+            return &self.primary;
+        }
+
+        let index = match self.fallbacks.iter().position(|x| Self::has(x, c)) {
+            Some(index) => Some(index),
+            None => loop {
+                let Some(id) = self.pending.pop() else {
+                    self.missing.insert(c);
+                    break None;
+                };
+
+                if let Some(font) = Self::load(&self.database, id) {
+                    self.fallbacks.push(font);
+
+                    if Self::has(self.fallbacks.last().expect("just pushed"), c) {
+                        break Some(self.fallbacks.len() - 1);
+                    }
+                }
+            },
+        };
+
+        // draws the primary face's missing glyph box
+        index.map_or(&self.primary, |i| &self.fallbacks[i])
+    }
+}
+
+/// A picture drawn on the CPU, so it renders without a GPU or a window.
+pub struct Canvas {
+    pixmap: Pixmap,
+    fonts: Fonts,
+}
+
+fn paint(color: Color) -> Paint<'static> {
+    let [r, g, b, a] = color.into_rgba8();
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(r, g, b, a);
+    paint
+}
+
+impl Canvas {
+    pub fn new(width: u32, height: u32, background: Color) -> Result<Self, Failure> {
+        let mut pixmap = Pixmap::new(width, height).ok_or(Failure::Render)?;
+        let [r, g, b, a] = background.into_rgba8();
+        pixmap.fill(tiny_skia::Color::from_rgba8(r, g, b, a));
+
+        Ok(Self {
+            pixmap,
+            fonts: Fonts::new()?,
+        })
+    }
+
+    pub fn fill_rect(&mut self, x: f32, y: f32, width: f32, height: f32, color: Color) {
+        if let Some(rect) = Rect::from_xywh(x, y, width, height) {
+            self.pixmap
+                .fill_rect(rect, &paint(color), Transform::identity(), None);
+        }
+    }
+
+    pub fn stroke_rect(&mut self, x: f32, y: f32, width: f32, height: f32, color: Color) {
+        if let Some(path) = Rect::from_xywh(x, y, width, height).map(PathBuilder::from_rect) {
+            self.pixmap.stroke_path(
+                &path,
+                &paint(color),
+                &Stroke::default(),
+                Transform::identity(),
+                None,
+            );
+        }
+    }
+
+    fn blend(&mut self, x: i32, y: i32, color: Color, coverage: f32) {
+        let (width, height) = (self.pixmap.width() as i32, self.pixmap.height() as i32);
+
+        if !(0..width).contains(&x) || !(0..height).contains(&y) {
+            return;
+        }
+
+        let pixel = &mut self.pixmap.pixels_mut()[(y * width + x) as usize];
+        let alpha = coverage.clamp(0.0, 1.0) * color.a;
+        let mix = |src: f32, dst: u8| (src * alpha * 255.0 + dst as f32 * (1.0 - alpha)) as u8;
+
+        if let Some(mixed) = PremultipliedColorU8::from_rgba(
+            mix(color.r, pixel.red()),
+            mix(color.g, pixel.green()),
+            mix(color.b, pixel.blue()),
+            mix(1.0, pixel.alpha()),
+        ) {
+            *pixel = mixed;
+        }
+    }
+
+    fn text_width(&mut self, size: f32, text: &str) -> f32 {
+        text.chars()
+            .map(|c| {
+                let font = self.fonts.font_for(c).as_scaled(PxScale::from(size));
+                font.h_advance(font.glyph_id(c))
+            })
+            .sum()
+    }
+
+    /// `y` is the top of the line.
+    pub fn text(&mut self, x: f32, y: f32, size: f32, color: Color, align: Horizontal, text: &str) {
+        let mut x = match align {
+            Horizontal::Left => x,
+            Horizontal::Center => x - self.text_width(size, text) / 2.0,
+            Horizontal::Right => x - self.text_width(size, text),
+        };
+
+        for c in text.chars() {
+            let font = self.fonts.font_for(c);
+            let scaled = font.as_scaled(PxScale::from(size));
+            let mut glyph = scaled.scaled_glyph(c);
+            let advance = scaled.h_advance(glyph.id);
+
+            glyph.position = point(x, y + scaled.ascent());
+
+            if let Some(outlined) = font.outline_glyph(glyph) {
+                let bounds = outlined.px_bounds();
+                let mut covered = Vec::new();
+
+                outlined.draw(|gx, gy, coverage| {
+                    covered.push((
+                        bounds.min.x as i32 + gx as i32,
+                        bounds.min.y as i32 + gy as i32,
+                        coverage,
+                    ));
+                });
+
+                for (px, py, coverage) in covered {
+                    self.blend(px, py, color, coverage);
+                }
+            }
+
+            x += advance;
+        }
+    }
+
+    pub fn encode(&self) -> Result<Vec<u8>, Failure> {
+        self.pixmap.encode_png().map_err(|_| Failure::Render)
+    }
+}
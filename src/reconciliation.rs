@@ -0,0 +1,100 @@
+use crate::{cell::Cell, config::Type, storage::Configs, tax::Totals};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use time::Date;
+
+/// Hours and amount of one payslip line. Either may be unknown: per-time
+/// configs have no hours, and a payslip need not itemize every config.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct Line {
+    pub minutes: Option<u32>,
+    pub amount: Option<u32>,
+}
+
+impl Line {
+    /// Only what both sides state is compared.
+    pub fn differs(&self, other: &Self) -> bool {
+        let differs = |a: Option<u32>, b: Option<u32>| a.zip(b).is_some_and(|(a, b)| a != b);
+        differs(self.minutes, other.minutes) || differs(self.amount, other.amount)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Breakdown {
+    pub total: Line,
+    pub configs: BTreeMap<String, Line>,
+}
+
+impl Breakdown {
+    /// Confirmed shifts only, since a payslip covers the work actually done.
+    /// Amounts are tax-included like every other total; the total rounds tax
+    /// once per rate (see [`Totals`]).
+    pub fn expected<'a>(configs: &Configs, cells: impl IntoIterator<Item = &'a Cell>) -> Self {
+        let cells: Vec<_> = cells.into_iter().collect();
+        let count = |name: &str| cells.iter().filter(|x| x.is_confirmed(name)).count();
+        let mut minutes = 0;
+        let mut lines = BTreeMap::new();
+
+        for (name, config) in configs {
+            let count = count(name);
+
+            if count == 0 {
+                continue;
+            }
+
+            let line = Line {
+                minutes: match config.r#type {
+                    Type::PerHour(hm) => Some(hm.minutes() as u32 * count as u32),
+                    Type::PerTime => None,
+                },
+                amount: Some(config.totals(count).total()),
+            };
+
+            minutes += line.minutes.unwrap_or(0);
+            lines.insert(name.clone(), line);
+        }
+
+        Self {
+            total: Line {
+                minutes: Some(minutes),
+                amount: Some(Totals::of(configs, count).total()),
+            },
+            configs: lines,
+        }
+    }
+
+    /// Names of the configs whose lines disagree, plus `None` for the total.
+    /// `self` is what was expected: a config missing from it had no shifts,
+    /// so a payslip line for it is compared against zero.
+    pub fn discrepancies<'a>(&'a self, other: &'a Self) -> Vec<Option<&'a str>> {
+        let zero = Line {
+            minutes: Some(0),
+            amount: Some(0),
+        };
+        let names: BTreeSet<_> = self.configs.keys().chain(other.configs.keys()).collect();
+        let total = self.total.differs(&other.total).then_some(None);
+        let configs = names.into_iter().filter_map(|name| {
+            let expected = self.configs.get(name).unwrap_or(&zero);
+            let stated = other.configs.get(name).copied().unwrap_or_default();
+
+            expected.differs(&stated).then_some(Some(name.as_str()))
+        });
+
+        total.into_iter().chain(configs).collect()
+    }
+}
+
+/// A payslip checked against the app's own figures for a period.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Reconciliation {
+    pub checked: Date,
+    /// As computed when the payslip was entered.
+    pub expected: Breakdown,
+    pub payslip: Breakdown,
+}
+
+impl Reconciliation {
+    pub fn discrepancies(&self) -> usize {
+        self.expected.discrepancies(&self.payslip).len()
+    }
+}
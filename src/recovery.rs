@@ -0,0 +1,176 @@
+use crate::{
+    cell::Assigned,
+    config::{TaxBasis, TypeForPickList},
+    failure::{Cause, Failure},
+    offset::Offset,
+    save_data::{self, PeriodRecord},
+    storage::Configs,
+    versioned::{self, Versioned},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use time::{Date, Month};
+
+/// Everything needed to bring the window back to where it was.
+#[derive(Serialize, Deserialize)]
+pub struct Snapshot {
+    pub month: Month,
+    pub offset: Offset,
+    pub year: i32,
+    pub custom_range: Option<(Date, Date)>,
+    pub configs: Configs,
+    /// Of the period on screen.
+    pub assignments: Vec<(Date, Assigned)>,
+    /// Of every other period.
+    pub periods: Vec<PeriodRecord>,
+    pub name_input: String,
+    pub type_selected: TypeForPickList,
+    pub pay_input: String,
+    #[serde(default)]
+    pub tax_basis_selected: TaxBasis,
+    #[serde(default)]
+    pub tax_rate_input: String,
+    pub hour_input: String,
+    pub minute_input: String,
+    pub filename_input: String,
+    pub filename_selected: Option<String>,
+}
+
+impl Versioned for Snapshot {
+    const VERSION: u64 = 1;
+
+    fn migrate(value: Value, from: u64) -> Result<Value, Cause> {
+        match from {
+            // unversioned files may come from either side of planning shifts
+            0 => {
+                let mut value = value;
+                value["version"] = json!(1);
+                save_data::confirm_all(&mut value["assignments"]);
+
+                for period in value["periods"].as_array_mut().into_iter().flatten() {
+                    save_data::confirm_all(&mut period["assignments"]);
+                }
+
+                Ok(value)
+            }
+            _ => Err(Cause::Version(from)),
+        }
+    }
+}
+
+pub fn from_str(read: &str) -> Result<Snapshot, Cause> {
+    versioned::from_str(read)
+}
+
+pub fn to_string(snapshot: &Snapshot) -> Result<String, Cause> {
+    versioned::to_string(snapshot)
+}
+
+/// The file exists for as long as a session runs, so finding it at startup
+/// means the previous session did not exit cleanly.
+pub struct Recovery {
+    path: PathBuf,
+}
+
+impl Recovery {
+    pub fn new(data_dir: &Path) -> Self {
+        Self {
+            path: data_dir.join("recovery.json"),
+        }
+    }
+
+    /// `None` if the previous session exited cleanly.
+    pub fn load(&self) -> Result<Option<Snapshot>, Failure> {
+        let read = match fs::read_to_string(&self.path) {
+            Ok(read) => read,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(Failure::Load(self.path.clone(), Cause::Io(e))),
+        };
+
+        from_str(&read)
+            .map(Some)
+            .map_err(|cause| Failure::Load(self.path.clone(), cause))
+    }
+
+    pub fn save(&self, to_write: &str) -> Result<(), Failure> {
+        let save = || -> Result<(), Cause> {
+            if let Some(parent) = self.path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+
+            Ok(fs::write(&self.path, to_write)?)
+        };
+
+        save().map_err(|cause| Failure::Autosave(self.path.clone(), cause))
+    }
+
+    pub fn remove(&self) -> Result<(), Failure> {
+        match fs::remove_file(&self.path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(Failure::Autosave(self.path.clone(), Cause::Io(e)))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::Status;
+
+    /// As written before 044, with no version and lists of names.
+    const V0: &str = r#"{
+        "month": "October",
+        "offset": { "Day": 1 },
+        "year": 2026,
+        "custom_range": null,
+        "configs": { "Lesson": { "type": "PerTime", "pay": 3000 } },
+        "assignments": [["2026-10-05", ["Lesson"]]],
+        "periods": [{
+            "start": "2026-09-01",
+            "end": "2026-10-01",
+            "assignments": [["2026-09-07", ["Lesson"]]]
+        }],
+        "name_input": "",
+        "type_selected": "PerTime",
+        "pay_input": "",
+        "hour_input": "",
+        "minute_input": "",
+        "filename_input": "shop",
+        "filename_selected": "shop"
+    }"#;
+
+    #[test]
+    fn v0_lists_of_names() {
+        let snapshot = from_str(V0).unwrap();
+
+        assert_eq!(snapshot.assignments[0].1["Lesson"], Status::Confirmed);
+        assert_eq!(
+            snapshot.periods[0].assignments[0].1["Lesson"],
+            Status::Confirmed
+        );
+        assert_eq!(snapshot.filename_selected.as_deref(), Some("shop"));
+    }
+
+    #[test]
+    fn v0_already_planned() {
+        let read = V0.replace(r#"["Lesson"]]]"#, r#"{ "Lesson": "Planned" }]]"#);
+        let snapshot = from_str(&read).unwrap();
+
+        assert_eq!(snapshot.assignments[0].1["Lesson"], Status::Planned);
+    }
+
+    #[test]
+    fn round_trip() {
+        let written = to_string(&from_str(V0).unwrap()).unwrap();
+        let value: Value = serde_json::from_str(&written).unwrap();
+
+        assert_eq!(value["version"], json!(Snapshot::VERSION));
+        assert!(from_str(&written).is_ok());
+    }
+}
@@ -0,0 +1,628 @@
+use crate::{
+    cell::{Assigned, Status},
+    document::{Document, escape},
+    storage::Configs,
+    summary::HEADER,
+    tax::Totals,
+    util,
+};
+use iced::Color;
+use std::fmt::Write;
+use time::{Date, Duration, Weekday};
+
+/// A period laid out for printing, to hand in as a timesheet.
+pub struct Report<'a> {
+    pub title: String,
+    pub start: Date,
+    /// Exclusive.
+    pub end: Date,
+    pub week_start: Weekday,
+    pub configs: &'a Configs,
+    pub assignments: Vec<(Date, Assigned)>,
+}
+
+struct Row<'a> {
+    name: &'a str,
+    pay: String,
+    duration: String,
+    count: usize,
+    /// At face value, like the pay.
+    sum: u32,
+}
+
+/// Planned shifts are drawn faded, like on screen. Opaque, unlike the
+/// HTML's opacity, so that nothing shows through.
+#[cfg(any(feature = "pdf", feature = "png"))]
+fn faded(color: Color) -> Color {
+    let mix = |x: f32| x * 0.35 + 0.65;
+    Color::from_rgb(mix(color.r), mix(color.g), mix(color.b))
+}
+
+fn css(color: Color) -> String {
+    let [r, g, b, _] = color.into_rgba8();
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+impl Report<'_> {
+    fn last(&self) -> Date {
+        self.end.previous_day().unwrap_or(self.end)
+    }
+
+    /// `2026-01-25_2026-02-24`, also used for the file name.
+    pub fn period(&self) -> String {
+        format!("{}_{}", self.start, self.last())
+    }
+
+    fn assigned(&self, date: Date) -> Option<&Assigned> {
+        self.assignments
+            .iter()
+            .find(|(x, _)| *x == date)
+            .map(|(_, names)| names)
+    }
+
+    /// Whole weeks covering the period.
+    fn weeks(&self) -> Vec<[Date; 7]> {
+        let first = if self.start.weekday() == self.week_start {
+            self.start
+        } else {
+            self.start.prev_occurrence(self.week_start)
+        };
+
+        (0..)
+            .map(|week| first + Duration::weeks(week))
+            .take_while(|x| *x < self.end)
+            .map(|x| std::array::from_fn(|i| x + Duration::days(i as i64)))
+            .collect()
+    }
+
+    fn rows(&self) -> Vec<Row<'_>> {
+        self.configs
+            .iter()
+            .map(|(name, config)| {
+                let count = self
+                    .assignments
+                    .iter()
+                    .filter(|(_, names)| names.contains_key(name))
+                    .count();
+
+                Row {
+                    name,
+                    pay: config.pay_to_string(),
+                    duration: config.r#type.duration_to_string().trim().to_string(),
+                    count,
+                    sum: config.sum(count),
+                }
+            })
+            .filter(|x| x.count > 0)
+            .collect()
+    }
+
+    /// Below the configs table: the totals with tax, then what of that was
+    /// confirmed.
+    fn footer(&self) -> Vec<(&'static str, u32)> {
+        let count = |name: &str, confirmed: bool| {
+            self.assignments
+                .iter()
+                .filter_map(|(_, names)| names.get(name))
+                .filter(|&&x| !confirmed || x == Status::Confirmed)
+                .count()
+        };
+        let mut retval = Totals::of(self.configs, |name| count(name, false))
+            .breakdown()
+            .to_vec();
+
+        retval.push((
+            "Confirmed (tax incl.)",
+            Totals::of(self.configs, |name| count(name, true)).total(),
+        ));
+        retval
+    }
+}
+
+impl Document for Report<'_> {
+    fn stem(&self) -> String {
+        format!("{}_{}", self.title, self.period())
+    }
+
+    fn to_html(&self) -> String {
+        let mut html = String::new();
+        let title = escape(&self.title);
+
+        let _ = write!(
+            html,
+            "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>{title} {period}</title>
+<style>
+body {{ font-family: sans-serif; margin: 2em; }}
+table {{ border-collapse: collapse; margin-bottom: 2em; }}
+th, td {{ border: 1px solid #999; padding: 2px 8px; }}
+.calendar {{ width: 100%; table-layout: fixed; }}
+.calendar td {{ height: 5em; vertical-align: top; padding: 4px; }}
+.calendar td.outside {{ color: #aaa; background: #f4f4f4; }}
+.shift {{ color: #fff; border-radius: 2px; margin-top: 2px; text-align: center; }}
+.planned {{ opacity: 0.35; }}
+.number {{ text-align: right; font-family: monospace; }}
+@media print {{ body {{ margin: 0; }} }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+<p>{start} - {last}</p>
+<table class=\"calendar\">
+<tr>",
+            period = self.period(),
+            start = self.start,
+            last = self.last(),
+        );
+
+        for weekday in util::weekdays(self.week_start) {
+            let _ = write!(html, "<th>{}</th>", util::short_weekday(weekday));
+        }
+
+        html.push_str("</tr>\n");
+
+        for week in self.weeks() {
+            html.push_str("<tr>");
+
+            for date in week {
+                let inside = self.start <= date && date < self.end;
+                let _ = write!(
+                    html,
+                    "<td{}>{} {}",
+                    if inside { "" } else { " class=\"outside\"" },
+                    util::short_month(date.month()),
+                    date.day()
+                );
+
+                for (name, status) in self.assigned(date).into_iter().flatten() {
+                    let _ = write!(
+                        html,
+                        "<div class=\"shift{}\" style=\"background: {}\">{}</div>",
+                        if *status == Status::Planned {
+                            " planned"
+                        } else {
+                            ""
+                        },
+                        css(util::get_color(name)),
+                        escape(name)
+                    );
+                }
+
+                html.push_str("</td>");
+            }
+
+            html.push_str("</tr>\n");
+        }
+
+        html.push_str(
+            "</table>
+<table>
+",
+        );
+        let _ = writeln!(
+            html,
+            "<tr>{}</tr>",
+            HEADER.map(|x| format!("<th>{x}</th>")).concat()
+        );
+
+        let rows = self.rows();
+
+        for row in &rows {
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td class=\"number\">{}</td><td class=\"number\">{}</td>\
+                 <td class=\"number\">{}</td><td class=\"number\">{}</td></tr>",
+                escape(row.name),
+                escape(&row.pay),
+                escape(&row.duration),
+                row.count,
+                util::yen(row.sum)
+            );
+        }
+
+        for (label, amount) in self.footer() {
+            let _ = writeln!(
+                html,
+                "<tr><th colspan=\"4\">{label}</th><td class=\"number\">{}</td></tr>",
+                util::yen(amount)
+            );
+        }
+
+        html.push_str(
+            "</table>
+</body>
+</html>
+",
+        );
+
+        html
+    }
+
+    #[cfg(feature = "pdf")]
+    fn to_pdf(&self) -> Result<Vec<u8>, crate::failure::Failure> {
+        self.render_pdf()
+    }
+
+    #[cfg(feature = "pdf")]
+    fn pdf_texts(&self) -> Vec<&str> {
+        std::iter::once(self.title.as_str())
+            .chain(self.configs.keys().map(String::as_str))
+            .collect()
+    }
+}
+
+#[cfg(feature = "pdf")]
+mod pdf {
+    use super::{HEADER, Report, faded};
+    use crate::{
+        cell::Status,
+        document::{
+            Document,
+            pdf::{Pages, SANS},
+        },
+        failure::Failure,
+        util,
+    };
+    use iced::Color;
+
+    /// A4 landscape, in points.
+    const WIDTH: f32 = 842.0;
+    const HEIGHT: f32 = 595.0;
+    const MARGIN: f32 = 36.0;
+    const CELL_WIDTH: f32 = 70.0;
+    const SHIFT_HEIGHT: f32 = 10.0;
+    const TABLE_X: f32 = MARGIN + 7.0 * CELL_WIDTH + 24.0;
+    const TABLE_COLUMNS: [f32; 5] = [70.0, 54.0, 42.0, 22.0, 68.0];
+    const LINE_HEIGHT: f32 = 14.0;
+    const GRAY: Color = Color::from_rgb(0.6, 0.6, 0.6);
+
+    /// Headings of the configs table, which is repeated on every page it
+    /// spans. Returns where the first row goes.
+    fn table_header(pages: &mut Pages, rights: &[f32], y: f32) -> f32 {
+        pages.fill(Color::BLACK);
+
+        for (i, label) in HEADER.into_iter().enumerate() {
+            // roughly right aligned, Helvetica averaging half an em
+            let x = match i {
+                0 => TABLE_X,
+                _ => rights[i] - label.len() as f32 * 4.4,
+            };
+            pages.text(SANS, 8.0, x, y - 9.0, label);
+        }
+
+        y - LINE_HEIGHT
+    }
+
+    /// The top of a page after the first.
+    fn next_page(pages: &mut Pages) -> f32 {
+        pages.next_page();
+        pages
+            .content()
+            .set_stroke_rgb(GRAY.r, GRAY.g, GRAY.b)
+            .set_line_width(0.5);
+        HEIGHT - MARGIN
+    }
+
+    impl Report<'_> {
+        pub fn render_pdf(&self) -> Result<Vec<u8>, Failure> {
+            let black = Color::BLACK;
+            let gray = GRAY;
+            let mut pages = Pages::new(WIDTH, HEIGHT, self.pdf_texts())?;
+            let mut y = HEIGHT - MARGIN - 16.0;
+
+            pages.fill(black).text(SANS, 16.0, MARGIN, y, &self.title);
+            y -= LINE_HEIGHT;
+            pages.text(
+                SANS,
+                9.0,
+                MARGIN,
+                y,
+                &format!("{} - {}", self.start, self.last()),
+            );
+            y -= LINE_HEIGHT + 4.0;
+
+            let top = y;
+
+            // calendar
+            for (i, weekday) in util::weekdays(self.week_start).into_iter().enumerate() {
+                pages.text(
+                    SANS,
+                    8.0,
+                    MARGIN + i as f32 * CELL_WIDTH + 2.0,
+                    y - 9.0,
+                    util::short_weekday(weekday),
+                );
+            }
+
+            y -= LINE_HEIGHT;
+
+            let weeks = self.weeks();
+            let cell_height = ((y - MARGIN) / weeks.len().max(1) as f32).min(80.0);
+
+            pages
+                .content()
+                .set_stroke_rgb(gray.r, gray.g, gray.b)
+                .set_line_width(0.5);
+
+            for week in &weeks {
+                for (i, date) in week.iter().enumerate() {
+                    let x = MARGIN + i as f32 * CELL_WIDTH;
+                    let inside = self.start <= *date && *date < self.end;
+
+                    if !inside {
+                        pages
+                            .fill(Color::from_rgb8(0xF4, 0xF4, 0xF4))
+                            .content()
+                            .rect(x, y - cell_height, CELL_WIDTH, cell_height)
+                            .fill_nonzero();
+                    }
+
+                    pages
+                        .content()
+                        .rect(x, y - cell_height, CELL_WIDTH, cell_height)
+                        .stroke();
+                    pages.fill(if inside { black } else { gray }).text(
+                        SANS,
+                        7.0,
+                        x + 2.0,
+                        y - 8.0,
+                        &format!("{} {}", util::short_month(date.month()), date.day()),
+                    );
+
+                    let mut shift_y = y - 10.0;
+
+                    for (name, status) in self.assigned(*date).into_iter().flatten() {
+                        let color = util::get_color(name);
+                        let color = match status {
+                            Status::Planned => faded(color),
+                            Status::Confirmed => color,
+                        };
+
+                        shift_y -= SHIFT_HEIGHT + 1.0;
+
+                        if shift_y < y - cell_height {
+                            break;
+                        }
+
+                        pages
+                            .fill(color)
+                            .content()
+                            .rect(x + 2.0, shift_y, CELL_WIDTH - 4.0, SHIFT_HEIGHT)
+                            .fill_nonzero();
+                        pages
+                            .fill(Color::WHITE)
+                            .text(SANS, 7.0, x + 4.0, shift_y + 2.5, name);
+                    }
+                }
+
+                y -= cell_height;
+            }
+
+            // configs table
+            let mut y = top;
+            let rights: Vec<f32> = TABLE_COLUMNS
+                .iter()
+                .scan(TABLE_X, |x, width| {
+                    *x += width;
+                    Some(*x)
+                })
+                .collect();
+
+            y = table_header(&mut pages, &rights, y);
+
+            for row in &self.rows() {
+                if y - LINE_HEIGHT < MARGIN {
+                    let top = next_page(&mut pages);
+                    y = table_header(&mut pages, &rights, top);
+                }
+
+                pages.text(SANS, 8.0, TABLE_X, y - 9.0, row.name);
+                pages.mono_right(8.0, rights[1], y - 9.0, &row.pay);
+                pages.mono_right(8.0, rights[2], y - 9.0, &row.duration);
+                pages.mono_right(8.0, rights[3], y - 9.0, &row.count.to_string());
+                pages.mono_right(8.0, rights[4], y - 9.0, &util::yen(row.sum));
+                y -= LINE_HEIGHT;
+            }
+
+            let footer = self.footer();
+
+            if y - footer.len() as f32 * LINE_HEIGHT < MARGIN {
+                y = next_page(&mut pages);
+                pages.fill(black);
+            }
+
+            pages
+                .content()
+                .move_to(TABLE_X, y)
+                .line_to(rights[4], y)
+                .stroke();
+
+            for (label, amount) in footer {
+                pages.text(SANS, 8.0, TABLE_X, y - 10.0, label);
+                pages.mono_right(8.0, rights[4], y - 10.0, &util::yen(amount));
+                y -= LINE_HEIGHT;
+            }
+
+            pages.finish()
+        }
+    }
+}
+
+#[cfg(feature = "png")]
+mod png {
+    use super::{Report, faded};
+    use crate::{
+        cell::Status,
+        failure::{Cause, Failure},
+        raster::Canvas,
+        util,
+    };
+    use iced::{Color, alignment::Horizontal};
+    use std::{
+        fs,
+        path::{Path, PathBuf},
+    };
+
+    const MARGIN: f32 = 24.0;
+    const CELL_WIDTH: f32 = 130.0;
+    const MIN_CELL_HEIGHT: f32 = 90.0;
+    const DATE_HEIGHT: f32 = 24.0;
+    const SHIFT_HEIGHT: f32 = 20.0;
+    const SHIFT_GAP: f32 = 2.0;
+    const ROW_HEIGHT: f32 = 26.0;
+    /// Name, pay, duration, count and sum.
+    const TABLE_COLUMNS: [f32; 5] = [270.0, 170.0, 110.0, 60.0, 180.0];
+    const TEXT_SIZE: f32 = 15.0;
+
+    impl Report<'_> {
+        pub fn to_png(&self) -> Result<Vec<u8>, Failure> {
+            let black = Color::BLACK;
+            let gray = Color::from_rgb8(0x99, 0x99, 0x99);
+            let weeks = self.weeks();
+            let rows = self.rows();
+            let footer = self.footer();
+
+            let most_shifts = self.assignments.iter().map(|(_, x)| x.len()).max();
+            let cell_height = (DATE_HEIGHT
+                + most_shifts.unwrap_or(0) as f32 * (SHIFT_HEIGHT + SHIFT_GAP))
+                .max(MIN_CELL_HEIGHT);
+            let calendar_top = MARGIN + 64.0;
+            let table_top = calendar_top + ROW_HEIGHT + weeks.len() as f32 * cell_height + MARGIN;
+            let width = 2.0 * MARGIN + 7.0 * CELL_WIDTH;
+            // with the header
+            let lines = 1 + rows.len() + footer.len();
+            let height = table_top + lines as f32 * ROW_HEIGHT + MARGIN;
+
+            let mut canvas = Canvas::new(width as u32, height as u32, Color::WHITE)?;
+
+            canvas.text(MARGIN, MARGIN, 28.0, black, Horizontal::Left, &self.title);
+            canvas.text(
+                MARGIN,
+                MARGIN + 36.0,
+                TEXT_SIZE,
+                black,
+                Horizontal::Left,
+                &format!("{} - {}", self.start, self.last()),
+            );
+
+            // calendar
+            for (i, weekday) in util::weekdays(self.week_start).into_iter().enumerate() {
+                canvas.text(
+                    MARGIN + (i as f32 + 0.5) * CELL_WIDTH,
+                    calendar_top + 4.0,
+                    TEXT_SIZE,
+                    black,
+                    Horizontal::Center,
+                    util::short_weekday(weekday),
+                );
+            }
+
+            for (row, week) in weeks.iter().enumerate() {
+                let y = calendar_top + ROW_HEIGHT + row as f32 * cell_height;
+
+                for (i, date) in week.iter().enumerate() {
+                    let x = MARGIN + i as f32 * CELL_WIDTH;
+                    let inside = self.start <= *date && *date < self.end;
+
+                    if !inside {
+                        canvas.fill_rect(
+                            x,
+                            y,
+                            CELL_WIDTH,
+                            cell_height,
+                            Color::from_rgb8(0xF4, 0xF4, 0xF4),
+                        );
+                    }
+
+                    canvas.stroke_rect(x, y, CELL_WIDTH, cell_height, gray);
+                    canvas.text(
+                        x + 4.0,
+                        y + 4.0,
+                        TEXT_SIZE,
+                        if inside { black } else { gray },
+                        Horizontal::Left,
+                        &format!("{} {}", util::short_month(date.month()), date.day()),
+                    );
+
+                    let assigned = self.assigned(*date).into_iter().flatten();
+
+                    for (j, (name, status)) in assigned.enumerate() {
+                        let color = util::get_color(name);
+                        let color = match status {
+                            Status::Planned => faded(color),
+                            Status::Confirmed => color,
+                        };
+                        let shift_y = y + DATE_HEIGHT + j as f32 * (SHIFT_HEIGHT + SHIFT_GAP);
+
+                        canvas.fill_rect(x + 4.0, shift_y, CELL_WIDTH - 8.0, SHIFT_HEIGHT, color);
+                        canvas.text(
+                            x + CELL_WIDTH / 2.0,
+                            shift_y + 2.0,
+                            TEXT_SIZE - 2.0,
+                            Color::WHITE,
+                            Horizontal::Center,
+                            name,
+                        );
+                    }
+                }
+            }
+
+            // configs table
+            let rights: Vec<f32> = TABLE_COLUMNS
+                .iter()
+                .scan(MARGIN, |x, width| {
+                    *x += width;
+                    Some(*x)
+                })
+                .collect();
+            let mut y = table_top;
+            let mut line = |canvas: &mut Canvas, cells: [&str; 5], color: Color| {
+                for (i, cell) in cells.into_iter().enumerate() {
+                    match i {
+                        0 => canvas.text(MARGIN, y, TEXT_SIZE, color, Horizontal::Left, cell),
+                        _ => canvas.text(rights[i], y, TEXT_SIZE, color, Horizontal::Right, cell),
+                    }
+                }
+
+                y += ROW_HEIGHT;
+            };
+
+            line(&mut canvas, super::HEADER, gray);
+
+            for row in &rows {
+                line(
+                    &mut canvas,
+                    [
+                        row.name,
+                        &row.pay,
+                        &row.duration,
+                        &row.count.to_string(),
+                        &util::yen(row.sum),
+                    ],
+                    black,
+                );
+            }
+
+            for (label, amount) in footer {
+                line(&mut canvas, [label, "", "", "", &util::yen(amount)], black);
+            }
+
+            canvas.encode()
+        }
+
+        /// Writes `<title>_<period>.png` into `dir`.
+        pub fn write_png(&self, dir: &Path) -> Result<PathBuf, Failure> {
+            let png = self.to_png()?;
+            let path = dir.join(format!("{}_{}.png", self.title, self.period()));
+
+            fs::create_dir_all(dir)
+                .and_then(|()| fs::write(&path, png))
+                .map_err(|e| Failure::Image(path.clone(), Cause::Io(e)))?;
+
+            Ok(path)
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use crate::{
+    cell::Assigned,
+    failure::Cause,
+    reconciliation::Reconciliation,
+    storage::Configs,
+    versioned::{self, Versioned},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+use time::Date;
+
+/// What a save file holds in the current version.
+#[derive(Serialize, Deserialize)]
+pub struct SaveData {
+    pub configs: Configs,
+    pub periods: Vec<PeriodRecord>,
+}
+
+/// The assignments made in one period. `end` is exclusive.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PeriodRecord {
+    pub start: Date,
+    pub end: Date,
+    pub assignments: Vec<(Date, Assigned)>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reconciliation: Option<Reconciliation>,
+    /// Income aimed for in this period, instead of the default in the settings.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub goal: Option<u32>,
+}
+
+/// Until shifts could be planned, each day held a list of names, all worked.
+/// Days already holding statuses are left as they are.
+pub fn confirm_all(assignments: &mut Value) {
+    for assignment in assignments.as_array_mut().into_iter().flatten() {
+        if let Some(names) = assignment[1].as_array() {
+            let names: Map<_, _> = names
+                .iter()
+                .filter_map(Value::as_str)
+                .map(|name| (name.to_string(), json!("Confirmed")))
+                .collect();
+
+            assignment[1] = Value::Object(names);
+        }
+    }
+}
+
+impl Versioned for SaveData {
+    const VERSION: u64 = 3;
+
+    fn migrate(value: Value, from: u64) -> Result<Value, Cause> {
+        match from {
+            // written before versioning: a bare map of configs
+            0 => Ok(json!({ "version": 1, "configs": value })),
+            1 => {
+                let mut value = value;
+                value["version"] = json!(2);
+                value["periods"] = json!([]);
+                Ok(value)
+            }
+            2 => {
+                let mut value = value;
+                value["version"] = json!(3);
+
+                for period in value["periods"].as_array_mut().into_iter().flatten() {
+                    confirm_all(&mut period["assignments"]);
+                }
+
+                Ok(value)
+            }
+            _ => Err(Cause::Version(from)),
+        }
+    }
+}
+
+pub fn from_str(read: &str) -> Result<SaveData, Cause> {
+    versioned::from_str(read)
+}
+
+pub fn to_string(data: &SaveData) -> Result<String, Cause> {
+    versioned::to_string(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cell::Status,
+        config::{TaxBasis, Type},
+    };
+    use time::Month;
+
+    fn date(day: u8) -> Date {
+        Date::from_calendar_date(2026, Month::October, day).unwrap()
+    }
+
+    /// Parses `read` and checks that writing it back uses the current version.
+    fn migrated(read: &str) -> SaveData {
+        let data = from_str(read).unwrap();
+        let written: Value = serde_json::from_str(&to_string(&data).unwrap()).unwrap();
+
+        assert_eq!(written["version"], json!(SaveData::VERSION));
+        data
+    }
+
+    #[test]
+    fn v0_bare_configs() {
+        let data = migrated(
+            r#"{
+                "Morning": { "type": { "PerHour": { "hour": 4, "minute": 30 } }, "pay": 1200 },
+                "version": { "type": "PerTime", "pay": 5000 }
+            }"#,
+        );
+
+        assert_eq!(data.configs.len(), 2);
+        assert!(matches!(data.configs["Morning"].r#type, Type::PerHour(_)));
+        assert_eq!(data.configs["Morning"].pay, 1200);
+        assert_eq!(data.configs["version"].pay, 5000);
+        assert_eq!(data.configs["version"].tax.basis, TaxBasis::Inclusive);
+        assert_eq!(data.configs["version"].tax.rate, 0);
+        assert!(data.periods.is_empty());
+    }
+
+    #[test]
+    fn v1_configs() {
+        let data = migrated(
+            r#"{
+                "version": 1,
+                "configs": { "Lesson": { "type": "PerTime", "pay": 3000 } }
+            }"#,
+        );
+
+        assert_eq!(data.configs["Lesson"].pay, 3000);
+        assert!(data.periods.is_empty());
+    }
+
+    #[test]
+    fn v2_assignments_become_confirmed() {
+        let data = migrated(
+            r#"{
+                "version": 2,
+                "configs": { "Lesson": { "type": "PerTime", "pay": 3000 } },
+                "periods": [{
+                    "start": "2026-10-01",
+                    "end": "2026-11-01",
+                    "assignments": [["2026-10-05", ["Lesson"]]]
+                }]
+            }"#,
+        );
+        let period = &data.periods[0];
+
+        assert_eq!(
+            (period.start, period.end),
+            (date(1), date(31).next_day().unwrap())
+        );
+        assert_eq!(period.assignments[0].0, date(5));
+        assert_eq!(period.assignments[0].1["Lesson"], Status::Confirmed);
+        assert!(period.reconciliation.is_none());
+        assert!(period.goal.is_none());
+    }
+
+    #[test]
+    fn v3_as_is() {
+        let data = migrated(
+            r#"{
+                "version": 3,
+                "configs": {
+                    "Lesson": {
+                        "type": "PerTime",
+                        "pay": 3000,
+                        "tax": { "basis": "Exclusive", "rate": 10 }
+                    }
+                },
+                "periods": [{
+                    "start": "2026-10-01",
+                    "end": "2026-11-01",
+                    "assignments": [["2026-10-05", { "Lesson": "Planned" }]],
+                    "goal": 120000
+                }]
+            }"#,
+        );
+
+        assert_eq!(data.configs["Lesson"].tax.basis, TaxBasis::Exclusive);
+        assert_eq!(data.configs["Lesson"].tax.rate, 10);
+        assert_eq!(data.periods[0].assignments[0].1["Lesson"], Status::Planned);
+        assert_eq!(data.periods[0].goal, Some(120000));
+    }
+
+    #[test]
+    fn newer_version() {
+        let read = format!(
+            r#"{{ "version": {}, "configs": {{}}, "periods": [] }}"#,
+            SaveData::VERSION + 1
+        );
+
+        assert!(matches!(from_str(&read), Err(Cause::Version(v)) if v == SaveData::VERSION + 1));
+    }
+}
@@ -0,0 +1,241 @@
+use crate::{
+    config::TypeForPickList,
+    failure::{Cause, Failure},
+    invoice,
+    offset::Offset,
+    storage, summary,
+};
+use iced::{Color, Size, Theme, theme};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{Display, Formatter},
+    fs,
+    path::PathBuf,
+};
+use time::{UtcOffset, Weekday};
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum WeekStart {
+    #[default]
+    Sunday,
+    Monday,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum Backend {
+    #[default]
+    Json,
+    /// Falls back to JSON when built without the `sqlite` feature.
+    Sqlite,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum Appearance {
+    #[default]
+    Calc,
+    Light,
+    Dark,
+}
+
+/// Which period is on screen at startup.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum DefaultPeriod {
+    #[default]
+    PreviousMonth,
+    CurrentMonth,
+    ContainingToday,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub week_start: WeekStart,
+    /// Used only when the system's local offset cannot be determined.
+    pub utc_offset: Option<(i8, i8)>,
+    /// Overrides the per-user application data directory.
+    pub data_dir: Option<PathBuf>,
+    /// How many backups to keep per save. 0 turns backups off.
+    pub backup_count: usize,
+    pub backend: Backend,
+    pub appearance: Appearance,
+    /// Updated whenever the window is resized, written on exit.
+    pub window_size: (f32, f32),
+    /// Opened at startup if it still exists.
+    pub last_file: Option<String>,
+    pub default_period: DefaultPeriod,
+    /// Of monthly periods, kept since saved periods are found by their dates.
+    pub offset: Offset,
+    pub default_type: TypeForPickList,
+    /// How long a per-time assignment is assumed to take, for rates.
+    pub per_time_minutes: u16,
+    /// Income aimed for in periods that have no goal of their own.
+    pub goal: Option<u32>,
+    pub invoice: invoice::Details,
+    pub summary_format: summary::Format,
+    /// Set once saves left in the working directory have been copied over.
+    pub legacy_imported: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            week_start: Default::default(),
+            utc_offset: None,
+            data_dir: None,
+            backup_count: 10,
+            backend: Default::default(),
+            appearance: Default::default(),
+            window_size: (1550.0, 800.0),
+            last_file: None,
+            default_period: Default::default(),
+            offset: Offset::Day(1),
+            default_type: TypeForPickList::PerHour,
+            per_time_minutes: 60,
+            goal: None,
+            invoice: Default::default(),
+            summary_format: Default::default(),
+            legacy_imported: false,
+        }
+    }
+}
+
+pub const WEEK_STARTS: [WeekStart; 2] = [WeekStart::Sunday, WeekStart::Monday];
+
+pub const APPEARANCES: [Appearance; 3] = [Appearance::Calc, Appearance::Light, Appearance::Dark];
+
+pub const DEFAULT_PERIODS: [DefaultPeriod; 3] = [
+    DefaultPeriod::PreviousMonth,
+    DefaultPeriod::CurrentMonth,
+    DefaultPeriod::ContainingToday,
+];
+
+#[cfg(feature = "sqlite")]
+pub const BACKENDS: [Backend; 2] = [Backend::Json, Backend::Sqlite];
+
+impl WeekStart {
+    pub const fn weekday(&self) -> Weekday {
+        match self {
+            Self::Sunday => Weekday::Sunday,
+            Self::Monday => Weekday::Monday,
+        }
+    }
+}
+
+impl Display for WeekStart {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Sunday => write!(f, "Sunday first"),
+            Self::Monday => write!(f, "Monday first"),
+        }
+    }
+}
+
+impl Appearance {
+    pub fn theme(&self) -> Theme {
+        match self {
+            Self::Calc => Theme::custom(
+                "Calc".to_string(),
+                theme::Palette {
+                    background: Color::from_rgb8(31, 31, 31),
+                    text: Color::from_rgb8(0xFF, 0xFF, 0xFF),
+                    primary: Color::from_rgb8(0, 0x3F, 0x7F),
+                    danger: Color::from_rgb8(0xFF, 0, 0),
+                    success: Color::from_rgb8(0, 0xFF, 0),
+                },
+            ),
+            Self::Light => Theme::Light,
+            Self::Dark => Theme::Dark,
+        }
+    }
+}
+
+impl Display for Appearance {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Calc => write!(f, "Calc"),
+            Self::Light => write!(f, "Light"),
+            Self::Dark => write!(f, "Dark"),
+        }
+    }
+}
+
+impl Display for DefaultPeriod {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PreviousMonth => write!(f, "Previous month"),
+            Self::CurrentMonth => write!(f, "Current month"),
+            Self::ContainingToday => write!(f, "Period containing today"),
+        }
+    }
+}
+
+impl Display for Backend {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Json => write!(f, "JSON files"),
+            Self::Sqlite => write!(f, "SQLite"),
+        }
+    }
+}
+
+impl Settings {
+    const MIN_WINDOW_WIDTH: f32 = 400.0;
+    const MIN_WINDOW_HEIGHT: f32 = 300.0;
+
+    fn path() -> PathBuf {
+        dirs::config_dir()
+            .unwrap_or_default()
+            .join("calc")
+            .join("settings.json")
+    }
+
+    pub fn data_dir(&self) -> PathBuf {
+        self.data_dir
+            .clone()
+            .unwrap_or_else(storage::default_data_dir)
+    }
+
+    pub fn window_size(&self) -> Size {
+        let (width, height) = self.window_size;
+        Size::new(
+            width.max(Self::MIN_WINDOW_WIDTH),
+            height.max(Self::MIN_WINDOW_HEIGHT),
+        )
+    }
+
+    pub fn utc_offset(&self) -> Option<UtcOffset> {
+        self.utc_offset
+            .and_then(|(hours, minutes)| UtcOffset::from_hms(hours, minutes, 0).ok())
+    }
+
+    pub fn set_utc_offset(&mut self, offset: Option<UtcOffset>) {
+        self.utc_offset = offset.map(|x| (x.whole_hours(), x.minutes_past_hour()));
+    }
+
+    /// The defaults if there is no file yet.
+    pub fn load() -> Result<Self, Failure> {
+        let path = Self::path();
+        let read = match fs::read_to_string(&path) {
+            Ok(read) => read,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(Failure::Settings(path, Cause::Io(e))),
+        };
+
+        serde_json::from_str(&read).map_err(|e| Failure::Settings(path, e.into()))
+    }
+
+    pub fn save(&self) -> Result<(), Failure> {
+        let path = Self::path();
+        let write = || -> Result<(), Cause> {
+            let to_write = serde_json::to_string(self)?;
+
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+
+            Ok(fs::write(&path, to_write)?)
+        };
+
+        write().map_err(|cause| Failure::Settings(path.clone(), cause))
+    }
+}
@@ -0,0 +1,444 @@
+use crate::{
+    cell::Status,
+    config::{Config, HourMinute, Tax, TaxBasis, Type},
+    failure::{Cause, Failure},
+    save_data::{self, PeriodRecord, SaveData},
+    storage::{self, Backup, Storage},
+};
+use rusqlite::{Connection, OptionalExtension, params};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+use time::{Date, OffsetDateTime, format_description::well_known::Iso8601};
+
+/// Keeps every save in one database. A save is stored as an employer that
+/// owns its configs and periods, so they can be queried across saves.
+pub struct SqliteStorage {
+    path: PathBuf,
+}
+
+/// Bump together with a new arm in [`SqliteStorage::open`].
+const SCHEMA_VERSION: i32 = 6;
+
+const SCHEMA: &str = "
+    CREATE TABLE employers (
+        id INTEGER PRIMARY KEY,
+        name TEXT NOT NULL UNIQUE
+    );
+    CREATE TABLE configs (
+        employer_id INTEGER NOT NULL REFERENCES employers (id) ON DELETE CASCADE,
+        name TEXT NOT NULL,
+        pay INTEGER NOT NULL,
+        -- NULL for per-time configs
+        minutes INTEGER,
+        PRIMARY KEY (employer_id, name)
+    );
+    CREATE TABLE periods (
+        id INTEGER PRIMARY KEY,
+        employer_id INTEGER NOT NULL REFERENCES employers (id) ON DELETE CASCADE,
+        start TEXT NOT NULL,
+        -- exclusive
+        end TEXT NOT NULL
+    );
+    CREATE TABLE assignments (
+        period_id INTEGER NOT NULL REFERENCES periods (id) ON DELETE CASCADE,
+        date TEXT NOT NULL,
+        config_name TEXT NOT NULL,
+        PRIMARY KEY (period_id, date, config_name)
+    );
+";
+
+/// Rows from before shifts could be planned were all worked.
+const ADD_CONFIRMED: &str = "
+    ALTER TABLE assignments ADD COLUMN confirmed INTEGER NOT NULL DEFAULT 1;
+";
+
+/// Kept as JSON since nothing queries into it.
+const ADD_RECONCILIATION: &str = "
+    ALTER TABLE periods ADD COLUMN reconciliation TEXT;
+";
+
+/// Existing configs keep their pay as it is: inclusive at 0%.
+const ADD_TAX: &str = "
+    ALTER TABLE configs ADD COLUMN tax_exclusive INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE configs ADD COLUMN tax_rate INTEGER NOT NULL DEFAULT 0;
+";
+
+/// Not tied to employers, so that deleting a save keeps its backups.
+const ADD_BACKUPS: &str = "
+    CREATE TABLE backups (
+        name TEXT NOT NULL,
+        stamp TEXT NOT NULL,
+        -- the save as written to a JSON file
+        data TEXT NOT NULL,
+        PRIMARY KEY (name, stamp)
+    );
+";
+
+/// Periods saved before goals were per period fall back to the default.
+const ADD_GOAL: &str = "
+    ALTER TABLE periods ADD COLUMN goal INTEGER;
+";
+
+fn parse_date(text: &str) -> Result<Date, rusqlite::Error> {
+    Date::parse(text, &Iso8601::DATE).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
+
+fn config_from_row(pay: u32, minutes: Option<u16>, tax: Tax) -> Result<Config, rusqlite::Error> {
+    let r#type = match minutes {
+        None => Type::PerTime,
+        Some(minutes) => Type::PerHour(
+            HourMinute::from_hm((minutes / 60) as u8, (minutes % 60) as u8).map_err(|e| {
+                rusqlite::Error::FromSqlConversionFailure(
+                    0,
+                    rusqlite::types::Type::Integer,
+                    Box::new(e),
+                )
+            })?,
+        ),
+    };
+
+    Ok(Config { r#type, pay, tax })
+}
+
+impl SqliteStorage {
+    pub fn new(data_dir: &Path) -> Self {
+        Self {
+            path: data_dir.join("calc.sqlite3"),
+        }
+    }
+
+    fn failure(&self, f: fn(PathBuf, Cause) -> Failure) -> impl Fn(rusqlite::Error) -> Failure {
+        move |e| f(self.path.clone(), Cause::Sqlite(e))
+    }
+
+    /// Creates or upgrades the schema as needed.
+    fn open(&self) -> Result<Connection, rusqlite::Error> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+        }
+
+        let connection = Connection::open(&self.path)?;
+        connection.pragma_update(None, "foreign_keys", true)?;
+
+        let version: i32 = connection.pragma_query_value(None, "user_version", |row| row.get(0))?;
+
+        for from in version..SCHEMA_VERSION {
+            match from {
+                0 => connection.execute_batch(SCHEMA)?,
+                1 => connection.execute_batch(ADD_CONFIRMED)?,
+                2 => connection.execute_batch(ADD_RECONCILIATION)?,
+                3 => connection.execute_batch(ADD_TAX)?,
+                4 => connection.execute_batch(ADD_BACKUPS)?,
+                5 => connection.execute_batch(ADD_GOAL)?,
+                _ => unreachable!("below SCHEMA_VERSION"),
+            }
+
+            connection.pragma_update(None, "user_version", from + 1)?;
+        }
+
+        Ok(connection)
+    }
+
+    fn employer_id(connection: &Connection, name: &str) -> Result<Option<i64>, rusqlite::Error> {
+        connection
+            .query_row("SELECT id FROM employers WHERE name = ?1", [name], |row| {
+                row.get(0)
+            })
+            .optional()
+    }
+
+    fn load_with(connection: &Connection, employer_id: i64) -> Result<SaveData, rusqlite::Error> {
+        let mut configs = BTreeMap::new();
+        let mut statement =
+            connection.prepare(
+                "SELECT name, pay, minutes, tax_exclusive, tax_rate FROM configs WHERE employer_id = ?1",
+            )?;
+        let mut rows = statement.query([employer_id])?;
+
+        while let Some(row) = rows.next()? {
+            let tax = Tax {
+                basis: if row.get(3)? {
+                    TaxBasis::Exclusive
+                } else {
+                    TaxBasis::Inclusive
+                },
+                rate: row.get(4)?,
+            };
+
+            configs.insert(row.get(0)?, config_from_row(row.get(1)?, row.get(2)?, tax)?);
+        }
+
+        let mut periods = Vec::new();
+        let mut statement = connection.prepare(
+            "SELECT id, start, end, reconciliation, goal FROM periods WHERE employer_id = ?1 ORDER BY start, end",
+        )?;
+        let mut rows = statement.query([employer_id])?;
+
+        while let Some(row) = rows.next()? {
+            let period_id: i64 = row.get(0)?;
+            let mut assignments: BTreeMap<Date, _> = BTreeMap::new();
+            let mut statement = connection.prepare(
+                "SELECT date, config_name, confirmed FROM assignments WHERE period_id = ?1",
+            )?;
+            let mut assignment_rows = statement.query([period_id])?;
+
+            while let Some(row) = assignment_rows.next()? {
+                let date = parse_date(&row.get::<_, String>(0)?)?;
+                let status = if row.get(2)? {
+                    Status::Confirmed
+                } else {
+                    Status::Planned
+                };
+
+                assignments
+                    .entry(date)
+                    .or_insert_with(BTreeMap::new)
+                    .insert(row.get(1)?, status);
+            }
+
+            periods.push(PeriodRecord {
+                start: parse_date(&row.get::<_, String>(1)?)?,
+                end: parse_date(&row.get::<_, String>(2)?)?,
+                assignments: assignments.into_iter().collect(),
+                reconciliation: row
+                    .get::<_, Option<String>>(3)?
+                    .map(|x| serde_json::from_str(&x))
+                    .transpose()
+                    .map_err(|e| {
+                        rusqlite::Error::FromSqlConversionFailure(
+                            3,
+                            rusqlite::types::Type::Text,
+                            Box::new(e),
+                        )
+                    })?,
+                goal: row.get(4)?,
+            });
+        }
+
+        Ok(SaveData { configs, periods })
+    }
+}
+
+impl Storage for SqliteStorage {
+    fn list(&self) -> Result<Vec<String>, Failure> {
+        if !self.path.is_file() {
+            return Ok(Vec::new());
+        }
+
+        let list = || -> Result<Vec<String>, rusqlite::Error> {
+            let connection = self.open()?;
+            let mut statement = connection.prepare("SELECT name FROM employers ORDER BY name")?;
+            let names = statement.query_map([], |row| row.get(0))?;
+
+            names.collect()
+        };
+
+        list().map_err(self.failure(Failure::Load))
+    }
+
+    fn exists(&self, name: &str) -> Result<bool, Failure> {
+        if !self.path.is_file() {
+            return Ok(false);
+        }
+
+        let exists = || Self::employer_id(&self.open()?, name);
+
+        exists()
+            .map(|id| id.is_some())
+            .map_err(self.failure(Failure::Load))
+    }
+
+    fn load(&self, name: &str) -> Result<SaveData, Failure> {
+        let load = || -> Result<Option<SaveData>, rusqlite::Error> {
+            let connection = self.open()?;
+
+            Self::employer_id(&connection, name)?
+                .map(|id| Self::load_with(&connection, id))
+                .transpose()
+        };
+
+        load()
+            .map_err(self.failure(Failure::Load))?
+            .ok_or_else(|| Failure::NoSuchSave(name.to_string()))
+    }
+
+    /// Replaces everything stored under `name` in one transaction.
+    fn save(&self, name: &str, data: &SaveData) -> Result<(), Failure> {
+        let save = || -> Result<(), rusqlite::Error> {
+            let mut connection = self.open()?;
+            let transaction = connection.transaction()?;
+
+            transaction.execute("DELETE FROM employers WHERE name = ?1", [name])?;
+            transaction.execute("INSERT INTO employers (name) VALUES (?1)", [name])?;
+
+            let employer_id = transaction.last_insert_rowid();
+
+            for (config_name, config) in &data.configs {
+                let minutes = match config.r#type {
+                    Type::PerTime => None,
+                    Type::PerHour(hm) => Some(hm.minutes()),
+                };
+
+                transaction.execute(
+                    "INSERT INTO configs (employer_id, name, pay, minutes, tax_exclusive, tax_rate) \
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![
+                        employer_id,
+                        config_name,
+                        config.pay,
+                        minutes,
+                        config.tax.basis == TaxBasis::Exclusive,
+                        config.tax.rate
+                    ],
+                )?;
+            }
+
+            for period in &data.periods {
+                let reconciliation = period
+                    .reconciliation
+                    .as_ref()
+                    .map(serde_json::to_string)
+                    .transpose()
+                    .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+
+                transaction.execute(
+                    "INSERT INTO periods (employer_id, start, end, reconciliation, goal) VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![
+                        employer_id,
+                        period.start.to_string(),
+                        period.end.to_string(),
+                        reconciliation,
+                        period.goal
+                    ],
+                )?;
+
+                let period_id = transaction.last_insert_rowid();
+
+                for (date, names) in &period.assignments {
+                    for (config_name, status) in names {
+                        transaction.execute(
+                            "INSERT INTO assignments (period_id, date, config_name, confirmed) VALUES (?1, ?2, ?3, ?4)",
+                            params![
+                                period_id,
+                                date.to_string(),
+                                config_name,
+                                *status == Status::Confirmed
+                            ],
+                        )?;
+                    }
+                }
+            }
+
+            transaction.commit()
+        };
+
+        save().map_err(self.failure(Failure::Save))
+    }
+
+    fn remove(&self, name: &str) -> Result<(), Failure> {
+        let remove = || -> Result<usize, rusqlite::Error> {
+            self.open()?
+                .execute("DELETE FROM employers WHERE name = ?1", [name])
+        };
+
+        match remove().map_err(self.failure(Failure::FileRemove))? {
+            0 => Err(Failure::NoSuchSave(name.to_string())),
+            _ => Ok(()),
+        }
+    }
+
+    fn backup(&self, name: &str, now: OffsetDateTime, keep: usize) -> Result<(), Failure> {
+        if keep == 0 || !self.path.is_file() {
+            return Ok(());
+        }
+
+        let backup = || -> Result<(), Cause> {
+            let connection = self.open()?;
+            let Some(employer_id) = Self::employer_id(&connection, name)? else {
+                return Ok(());
+            };
+            let data = save_data::to_string(&Self::load_with(&connection, employer_id)?)?;
+
+            connection.execute(
+                "INSERT OR REPLACE INTO backups (name, stamp, data) VALUES (?1, ?2, ?3)",
+                params![name, storage::stamp(now), data],
+            )?;
+            connection.execute(
+                "DELETE FROM backups WHERE name = ?1 AND stamp NOT IN \
+                 (SELECT stamp FROM backups WHERE name = ?1 ORDER BY stamp DESC LIMIT ?2)",
+                params![name, keep as i64],
+            )?;
+
+            Ok(())
+        };
+
+        backup().map_err(|cause| Failure::Backup(self.path.clone(), cause))
+    }
+
+    fn backups(&self) -> Result<Vec<Backup>, Failure> {
+        if !self.path.is_file() {
+            return Ok(Vec::new());
+        }
+
+        let list = || -> Result<Vec<Backup>, rusqlite::Error> {
+            let connection = self.open()?;
+            let mut statement = connection
+                .prepare("SELECT name, stamp, data FROM backups ORDER BY name, stamp DESC")?;
+            let backups = statement.query_map([], |row| {
+                Ok(Backup {
+                    name: row.get(0)?,
+                    stamp: row.get(1)?,
+                    config_count: save_data::from_str(&row.get::<_, String>(2)?)
+                        .ok()
+                        .map(|data| data.configs.len()),
+                })
+            })?;
+
+            backups.collect()
+        };
+
+        list().map_err(self.failure(Failure::Load))
+    }
+
+    /// The backup is read beforehand since backing up may prune it.
+    fn restore(&self, backup: &Backup, now: OffsetDateTime, keep: usize) -> Result<(), Failure> {
+        let read = || -> Result<SaveData, Cause> {
+            let data: String = self.open()?.query_row(
+                "SELECT data FROM backups WHERE name = ?1 AND stamp = ?2",
+                [&backup.name, &backup.stamp],
+                |row| row.get(0),
+            )?;
+
+            save_data::from_str(&data)
+        };
+        let data = read().map_err(|cause| Failure::Load(self.path.clone(), cause))?;
+
+        self.backup(&backup.name, now, keep)?;
+        self.save(&backup.name, &data)
+    }
+}
+
+/// Copies every JSON save into the database. Saves of the same name are
+/// replaced, backed up first as any overwritten save is. Returns how many
+/// were imported.
+pub fn import(
+    json: &impl Storage,
+    sqlite: &impl Storage,
+    now: OffsetDateTime,
+    keep: usize,
+) -> Result<usize, Failure> {
+    let names = json.list()?;
+
+    for name in &names {
+        let data = json.load(name)?;
+        sqlite.backup(name, now, keep)?;
+        sqlite.save(name, &data)?;
+    }
+
+    Ok(names.len())
+}
@@ -0,0 +1,63 @@
+use crate::{cell::Cell, config::Type, storage::Configs, tax::Totals};
+
+/// Figures derived from the assignments of one period. Amounts include tax.
+pub struct Statistics {
+    /// Planned and confirmed shifts alike.
+    pub sum: u32,
+    pub confirmed: u32,
+    /// Of per-hour configs only.
+    pub paid_minutes: u32,
+    /// Per-time assignments, each counted as the assumed duration.
+    pub assumed_minutes: u32,
+    pub days_worked: usize,
+}
+
+impl Statistics {
+    pub fn new<'a>(
+        configs: &Configs,
+        cells: impl IntoIterator<Item = &'a Cell>,
+        per_time_minutes: u32,
+    ) -> Self {
+        let mut retval = Self {
+            sum: 0,
+            confirmed: 0,
+            paid_minutes: 0,
+            assumed_minutes: 0,
+            days_worked: 0,
+        };
+
+        let cells: Vec<_> = cells
+            .into_iter()
+            .filter(|x| !x.config_names.is_empty())
+            .collect();
+
+        let count = |name: &str| cells.iter().filter(|x| x.contains(name)).count();
+        let confirmed = |name: &str| cells.iter().filter(|x| x.is_confirmed(name)).count();
+
+        retval.days_worked = cells.len();
+
+        for (name, config) in configs {
+            let count = count(name) as u32;
+
+            match config.r#type {
+                Type::PerHour(hm) => retval.paid_minutes += hm.minutes() as u32 * count,
+                Type::PerTime => retval.assumed_minutes += per_time_minutes * count,
+            }
+        }
+
+        retval.sum = Totals::of(configs, count).total();
+        retval.confirmed = Totals::of(configs, confirmed).total();
+
+        retval
+    }
+
+    pub fn per_day(&self) -> Option<u32> {
+        (self.days_worked > 0).then(|| self.sum / self.days_worked as u32)
+    }
+
+    /// Across all configs, per-time ones included.
+    pub fn hourly_rate(&self) -> Option<u32> {
+        let minutes = self.paid_minutes + self.assumed_minutes;
+        (minutes > 0).then(|| (self.sum as u64 * 60 / minutes as u64) as u32)
+    }
+}
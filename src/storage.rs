@@ -0,0 +1,546 @@
+use crate::{
+    config::Config,
+    failure::{Cause, Failure},
+    save_data::{self, SaveData},
+};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Mutex, PoisonError},
+    time::SystemTime,
+};
+use time::OffsetDateTime;
+
+pub type Configs = BTreeMap<String, Config>;
+
+/// Where saves are kept. Every backend stores the same [`SaveData`] by name.
+pub trait Storage {
+    /// Lists only the saves that actually load. Cheap enough to poll.
+    fn list(&self) -> Result<Vec<String>, Failure>;
+    fn exists(&self, name: &str) -> Result<bool, Failure>;
+    fn load(&self, name: &str) -> Result<SaveData, Failure>;
+    fn save(&self, name: &str, data: &SaveData) -> Result<(), Failure>;
+    fn remove(&self, name: &str) -> Result<(), Failure>;
+
+    /// Keeps a copy of the save (if it exists), then drops the oldest copies
+    /// beyond `keep`. Keeping 0 turns backups off.
+    fn backup(&self, name: &str, now: OffsetDateTime, keep: usize) -> Result<(), Failure>;
+    fn backups(&self) -> Result<Vec<Backup>, Failure>;
+    /// Puts the backup back in place, backing up the current save first.
+    fn restore(&self, backup: &Backup, now: OffsetDateTime, keep: usize) -> Result<(), Failure>;
+}
+
+pub fn default_data_dir() -> PathBuf {
+    dirs::data_dir().unwrap_or_default().join("calc")
+}
+
+/// Saves live in their own folder so that unrelated files are never listed.
+pub struct JsonStorage {
+    dir: PathBuf,
+    backups: PathBuf,
+}
+
+/// A copy of a save taken right before it was overwritten or deleted.
+pub struct Backup {
+    pub name: String,
+    /// When it was taken, as made by [`stamp`]. Sorts by time.
+    pub stamp: String,
+    pub config_count: Option<usize>,
+}
+
+/// `20261018-123005-123` for 12:30:05.123 on 2026-10-18.
+pub fn stamp(now: OffsetDateTime) -> String {
+    format!(
+        "{:04}{:02}{:02}-{:02}{:02}{:02}-{:03}",
+        now.year(),
+        now.month() as u8,
+        now.day(),
+        now.hour(),
+        now.minute(),
+        now.second(),
+        now.millisecond()
+    )
+}
+
+const EXTENSION: &str = "json";
+
+/// Whether each file parsed when last read, by modification time and size,
+/// so that polling reads only files that changed since.
+static PARSES: Mutex<BTreeMap<PathBuf, (SystemTime, u64, bool)>> = Mutex::new(BTreeMap::new());
+
+fn parses(path: &Path) -> bool {
+    let Ok((modified, len)) = fs::metadata(path).and_then(|x| Ok((x.modified()?, x.len()))) else {
+        return false;
+    };
+    let mut parses = PARSES.lock().unwrap_or_else(PoisonError::into_inner);
+
+    match parses.get(path) {
+        Some(&(at, at_len, retval)) if at == modified && at_len == len => retval,
+        _ => {
+            let retval =
+                fs::read_to_string(path).is_ok_and(|read| save_data::from_str(&read).is_ok());
+            parses.insert(path.to_path_buf(), (modified, len, retval));
+            retval
+        }
+    }
+}
+const MAX_NAME_LEN: usize = 100;
+
+/// Rejects anything that could escape the saves folder or that some
+/// platform cannot use as a file name.
+pub fn validate_name(name: &str) -> Result<(), Failure> {
+    const RESERVED: [&str; 4] = ["CON", "PRN", "AUX", "NUL"];
+
+    if name.is_empty() {
+        return Err(Failure::SaveEmptyName);
+    }
+
+    let stem = name
+        .split('.')
+        .next()
+        .unwrap_or_default()
+        .to_ascii_uppercase();
+    let reserved = RESERVED.contains(&stem.as_str())
+        || ((stem.starts_with("COM") || stem.starts_with("LPT"))
+            && stem.len() == 4
+            && stem.ends_with(|c: char| c.is_ascii_digit()));
+
+    let valid = name.chars().count() <= MAX_NAME_LEN
+        && !reserved
+        && !name.starts_with(['.', ' '])
+        && !name.ends_with(['.', ' '])
+        && !name.chars().any(|c| {
+            c.is_control() || matches!(c, '/' | '\\' | '<' | '>' | ':' | '"' | '|' | '?' | '*')
+        });
+
+    if valid {
+        Ok(())
+    } else {
+        Err(Failure::Filename)
+    }
+}
+
+/// Puts every name through [`validate_name`] before the backend sees it, and
+/// lists only names that pass, since names end up in file names elsewhere.
+pub struct Checked<S>(pub S);
+
+impl<S: Storage> Storage for Checked<S> {
+    fn list(&self) -> Result<Vec<String>, Failure> {
+        let mut retval = self.0.list()?;
+        retval.retain(|x| validate_name(x).is_ok());
+        Ok(retval)
+    }
+
+    fn exists(&self, name: &str) -> Result<bool, Failure> {
+        validate_name(name)?;
+        self.0.exists(name)
+    }
+
+    fn load(&self, name: &str) -> Result<SaveData, Failure> {
+        validate_name(name)?;
+        self.0.load(name)
+    }
+
+    fn save(&self, name: &str, data: &SaveData) -> Result<(), Failure> {
+        validate_name(name)?;
+        self.0.save(name, data)
+    }
+
+    fn remove(&self, name: &str) -> Result<(), Failure> {
+        validate_name(name)?;
+        self.0.remove(name)
+    }
+
+    fn backup(&self, name: &str, now: OffsetDateTime, keep: usize) -> Result<(), Failure> {
+        validate_name(name)?;
+        self.0.backup(name, now, keep)
+    }
+
+    fn backups(&self) -> Result<Vec<Backup>, Failure> {
+        let mut retval = self.0.backups()?;
+        retval.retain(|x| validate_name(&x.name).is_ok());
+        Ok(retval)
+    }
+
+    fn restore(&self, backup: &Backup, now: OffsetDateTime, keep: usize) -> Result<(), Failure> {
+        validate_name(&backup.name)?;
+        self.0.restore(backup, now, keep)
+    }
+}
+
+/// Saves used to be `*.json` files in the working directory. Copies those
+/// that parse as calc data, leaving names already taken alone. Returns the
+/// names copied.
+pub fn import_legacy(into: &dyn Storage) -> Result<Vec<String>, Failure> {
+    let dir = std::env::current_dir().map_err(|e| Failure::Load(".".into(), Cause::Io(e)))?;
+    let legacy = JsonStorage {
+        backups: dir.join("backups"),
+        dir,
+    };
+    let mut retval = Vec::new();
+
+    for name in legacy.list()? {
+        if !into.exists(&name)? {
+            into.save(&name, &legacy.load(&name)?)?;
+            retval.push(name);
+        }
+    }
+
+    Ok(retval)
+}
+
+impl JsonStorage {
+    pub fn new(data_dir: &Path) -> Self {
+        Self {
+            dir: data_dir.join("saves"),
+            backups: data_dir.join("backups"),
+        }
+    }
+
+    fn path(&self, name: &str) -> Result<PathBuf, Failure> {
+        validate_name(name)?;
+        Ok(self.dir.join(format!("{name}.{EXTENSION}")))
+    }
+
+    fn backup_path(&self, name: &str, stamp: &str) -> PathBuf {
+        self.backups.join(name).join(format!("{stamp}.{EXTENSION}"))
+    }
+
+    fn stamps(dir: &Path) -> Result<Vec<String>, Cause> {
+        let mut retval = Vec::new();
+
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+
+            if let Some(EXTENSION) = path.extension().and_then(|x| x.to_str())
+                && let Some(stamp) = path.file_stem().and_then(|x| x.to_str())
+            {
+                retval.push(stamp.to_string());
+            }
+        }
+
+        Ok(retval)
+    }
+}
+
+impl Storage for JsonStorage {
+    fn exists(&self, name: &str) -> Result<bool, Failure> {
+        Ok(self.path(name)?.is_file())
+    }
+
+    fn list(&self) -> Result<Vec<String>, Failure> {
+        let failure = |e| Failure::Load(self.dir.clone(), Cause::Io(e));
+        let dir = match fs::read_dir(&self.dir) {
+            Ok(dir) => dir,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(failure(e)),
+        };
+
+        let mut retval = Vec::new();
+
+        for entry in dir {
+            let path = entry.map_err(failure)?.path();
+
+            if let Some(EXTENSION) = path.extension().and_then(|x| x.to_str())
+                && let Some(filename) = path.file_stem().and_then(|x| x.to_str())
+                && validate_name(filename).is_ok()
+                && path.is_file()
+                && parses(&path)
+            {
+                retval.push(filename.to_string())
+            }
+        }
+
+        retval.sort();
+
+        Ok(retval)
+    }
+
+    fn load(&self, name: &str) -> Result<SaveData, Failure> {
+        let path = self.path(name)?;
+        let load = || save_data::from_str(&fs::read_to_string(&path)?);
+
+        load().map_err(|cause| Failure::Load(path.clone(), cause))
+    }
+
+    fn save(&self, name: &str, data: &SaveData) -> Result<(), Failure> {
+        let path = self.path(name)?;
+        let save = || -> Result<(), Cause> {
+            let to_write = save_data::to_string(data)?;
+            fs::create_dir_all(&self.dir)?;
+            Ok(fs::write(&path, to_write)?)
+        };
+
+        save().map_err(|cause| Failure::Save(path.clone(), cause))
+    }
+
+    fn remove(&self, name: &str) -> Result<(), Failure> {
+        let path = self.path(name)?;
+        fs::remove_file(&path).map_err(|e| Failure::FileRemove(path, Cause::Io(e)))
+    }
+
+    fn backup(&self, name: &str, now: OffsetDateTime, keep: usize) -> Result<(), Failure> {
+        let path = self.path(name)?;
+
+        if keep == 0 || !path.is_file() {
+            return Ok(());
+        }
+
+        let dir = self.backups.join(name);
+        let backup = || -> Result<(), Cause> {
+            fs::create_dir_all(&dir)?;
+            fs::copy(&path, self.backup_path(name, &stamp(now)))?;
+
+            let mut stamps = Self::stamps(&dir)?;
+            stamps.sort();
+
+            for stamp in stamps.iter().rev().skip(keep) {
+                fs::remove_file(dir.join(format!("{stamp}.{EXTENSION}")))?;
+            }
+
+            Ok(())
+        };
+
+        backup().map_err(|cause| Failure::Backup(dir.clone(), cause))
+    }
+
+    fn backups(&self) -> Result<Vec<Backup>, Failure> {
+        let list = || -> Result<Vec<Backup>, Cause> {
+            let dir = match fs::read_dir(&self.backups) {
+                Ok(dir) => dir,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+                Err(e) => return Err(e.into()),
+            };
+
+            let mut names = Vec::new();
+
+            for entry in dir {
+                let entry = entry?;
+
+                if entry.file_type()?.is_dir()
+                    && let Some(name) = entry.file_name().to_str()
+                    && validate_name(name).is_ok()
+                {
+                    names.push(name.to_string());
+                }
+            }
+
+            names.sort();
+
+            let mut retval = Vec::new();
+
+            for name in names {
+                let dir = self.backups.join(&name);
+                let mut stamps = Self::stamps(&dir)?;
+                stamps.sort();
+
+                for stamp in stamps.into_iter().rev() {
+                    let config_count = fs::read_to_string(self.backup_path(&name, &stamp))
+                        .ok()
+                        .and_then(|read| save_data::from_str(&read).ok())
+                        .map(|data| data.configs.len());
+
+                    retval.push(Backup {
+                        name: name.clone(),
+                        stamp,
+                        config_count,
+                    });
+                }
+            }
+
+            Ok(retval)
+        };
+
+        list().map_err(|cause| Failure::Load(self.backups.clone(), cause))
+    }
+
+    /// The backup is read beforehand since backing up may prune it.
+    fn restore(&self, backup: &Backup, now: OffsetDateTime, keep: usize) -> Result<(), Failure> {
+        let path = self.path(&backup.name)?;
+        let backup_path = self.backup_path(&backup.name, &backup.stamp);
+        let read = fs::read(&backup_path).map_err(|e| Failure::Load(backup_path, Cause::Io(e)))?;
+
+        self.backup(&backup.name, now, keep)?;
+
+        let restore = || -> Result<(), Cause> {
+            fs::create_dir_all(&self.dir)?;
+            Ok(fs::write(&path, read)?)
+        };
+
+        restore().map_err(|cause| Failure::Save(path.clone(), cause))
+    }
+}
+
+impl Backup {
+    /// `20261018-123005-123` becomes `2026-10-18 12:30:05`.
+    pub fn taken(&self) -> String {
+        let s = &self.stamp;
+
+        match (
+            s.get(0..4),
+            s.get(4..6),
+            s.get(6..8),
+            s.get(9..11),
+            s.get(11..13),
+            s.get(13..15),
+        ) {
+            (Some(y), Some(mo), Some(d), Some(h), Some(mi), Some(se)) => {
+                format!("{y}-{mo}-{d} {h}:{mi}:{se}")
+            }
+            _ => s.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_name() {
+        assert!(matches!(validate_name(""), Err(Failure::SaveEmptyName)));
+    }
+
+    #[test]
+    fn separators_and_parent() {
+        for name in ["a/b", "a\\b", "/", "\\", "..", "../a", "a/../b"] {
+            assert!(validate_name(name).is_err(), "{name}");
+        }
+    }
+
+    #[test]
+    fn reserved_names() {
+        for name in ["CON", "PRN", "AUX", "NUL", "con", "Nul.txt", "COM1", "lpt9"] {
+            assert!(validate_name(name).is_err(), "{name}");
+        }
+
+        for name in ["CONSOLE", "auxiliary", "COM", "COM10"] {
+            assert!(validate_name(name).is_ok(), "{name}");
+        }
+    }
+
+    #[test]
+    fn length() {
+        assert!(validate_name(&"a".repeat(MAX_NAME_LEN)).is_ok());
+        assert!(validate_name(&"a".repeat(MAX_NAME_LEN + 1)).is_err());
+        // counted in characters, not bytes
+        assert!(validate_name(&"あ".repeat(MAX_NAME_LEN)).is_ok());
+    }
+
+    #[test]
+    fn dots_keep_the_whole_name() {
+        let storage = JsonStorage::new(Path::new("data"));
+
+        for name in ["v1.2", "a.b.c", "2026.10"] {
+            assert!(validate_name(name).is_ok(), "{name}");
+            assert_eq!(
+                storage.path(name).unwrap(),
+                Path::new("data/saves").join(format!("{name}.json"))
+            );
+        }
+
+        assert!(validate_name(".hidden").is_err());
+        assert!(validate_name("trailing.").is_err());
+    }
+
+    /// A fresh data folder per test, since tests run in parallel.
+    fn data_dir(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("calc-{}-{test}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn data(pay: u32) -> SaveData {
+        let config = Config {
+            r#type: crate::config::Type::PerTime,
+            pay,
+            tax: Default::default(),
+        };
+
+        SaveData {
+            configs: Configs::from([("Lesson".to_string(), config)]),
+            periods: Vec::new(),
+        }
+    }
+
+    fn restore_the_only_backup(storage: &impl Storage) {
+        let now = OffsetDateTime::UNIX_EPOCH;
+
+        storage.save("shop", &data(1000)).unwrap();
+        storage.backup("shop", now, 1).unwrap();
+        storage.save("shop", &data(2000)).unwrap();
+
+        let backups = storage.backups().unwrap();
+        assert_eq!(backups.len(), 1);
+
+        // backing up the current save prunes the one being restored
+        let later = now + time::Duration::SECOND;
+        storage.restore(&backups[0], later, 1).unwrap();
+
+        assert_eq!(storage.load("shop").unwrap().configs["Lesson"].pay, 1000);
+        assert_eq!(storage.backups().unwrap().len(), 1);
+    }
+
+    fn keeping_none_takes_no_backup(storage: &impl Storage) {
+        storage.save("shop", &data(1000)).unwrap();
+        storage
+            .backup("shop", OffsetDateTime::UNIX_EPOCH, 0)
+            .unwrap();
+
+        assert!(storage.backups().unwrap().is_empty());
+    }
+
+    #[test]
+    fn json_backups() {
+        let dir = data_dir("json-backups");
+        restore_the_only_backup(&JsonStorage::new(&dir.join("restore")));
+        keeping_none_takes_no_backup(&JsonStorage::new(&dir.join("keep-none")));
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn lists_only_calc_data() {
+        let dir = data_dir("list");
+        let storage = JsonStorage::new(&dir);
+        storage.save("shop", &data(1000)).unwrap();
+        fs::write(dir.join("saves/package.json"), r#"{"name": "calc"}"#).unwrap();
+        assert_eq!(storage.list().unwrap(), ["shop"]);
+
+        // a file that changes is read again
+        storage.save("package", &data(1000)).unwrap();
+        assert_eq!(storage.list().unwrap(), ["package", "shop"]);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn sqlite_backups() {
+        use crate::sqlite::SqliteStorage;
+
+        let dir = data_dir("sqlite-backups");
+        restore_the_only_backup(&SqliteStorage::new(&dir.join("restore")));
+        keeping_none_takes_no_backup(&SqliteStorage::new(&dir.join("keep-none")));
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn import_backs_up_replaced_saves() {
+        use crate::sqlite::{self, SqliteStorage};
+
+        let dir = data_dir("sqlite-import");
+        let json = JsonStorage::new(&dir);
+        let sqlite = SqliteStorage::new(&dir);
+        json.save("shop", &data(1000)).unwrap();
+        sqlite.save("shop", &data(2000)).unwrap();
+
+        sqlite::import(&json, &sqlite, OffsetDateTime::UNIX_EPOCH, 1).unwrap();
+
+        assert_eq!(sqlite.load("shop").unwrap().configs["Lesson"].pay, 1000);
+        let backups = sqlite.backups().unwrap();
+        assert_eq!(backups.len(), 1);
+        assert_eq!(backups[0].name, "shop");
+        let _ = fs::remove_dir_all(dir);
+    }
+}
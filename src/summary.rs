@@ -0,0 +1,167 @@
+use crate::{
+    cell::Cell,
+    config::{Config, Type},
+    storage::Configs,
+    tax::Totals,
+    util,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+/// How the configs table is put onto the clipboard.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum Format {
+    /// Aligned like on screen.
+    #[default]
+    Text,
+    Markdown,
+    /// Plain numbers, for spreadsheets.
+    Tsv,
+}
+
+pub const FORMATS: [Format; 3] = [Format::Text, Format::Markdown, Format::Tsv];
+
+impl Display for Format {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Text => write!(f, "Text"),
+            Self::Markdown => write!(f, "Markdown"),
+            Self::Tsv => write!(f, "TSV"),
+        }
+    }
+}
+
+/// Headings of the configs table, wherever it is shown.
+pub const HEADER: [&str; 5] = ["Name", "Pay", "Duration", "#", "Face value"];
+
+type Row = [String; 5];
+
+/// Labels and amounts below the configs, as in the Result panel.
+type Footer = [(&'static str, u32); 3];
+
+struct Line<'a> {
+    name: &'a str,
+    config: &'a Config,
+    count: usize,
+}
+
+impl Line<'_> {
+    /// As on screen.
+    fn row(&self) -> Row {
+        [
+            self.name.to_string(),
+            self.config.pay_to_string(),
+            self.config.r#type.duration_to_string().trim().to_string(),
+            util::comma_separated(self.count as u32),
+            util::yen(self.config.sum(self.count)),
+        ]
+    }
+}
+
+fn total_row(label: &str, total: u32) -> Row {
+    [
+        label.to_string(),
+        String::new(),
+        String::new(),
+        String::new(),
+        util::yen(total),
+    ]
+}
+
+/// The name is left aligned, the figures right aligned.
+fn text(lines: &[Line], footer: &Footer) -> String {
+    let header = HEADER.map(str::to_string);
+    let rows: Vec<_> = std::iter::once(header)
+        .chain(lines.iter().map(Line::row))
+        .chain(
+            footer
+                .iter()
+                .map(|&(label, amount)| total_row(label, amount)),
+        )
+        .collect();
+    let widths: [usize; 5] =
+        std::array::from_fn(|i| rows.iter().map(|x| x[i].chars().count()).max().unwrap_or(0));
+
+    rows.iter()
+        .map(|row| {
+            let cells: Vec<_> = row
+                .iter()
+                .zip(widths)
+                .enumerate()
+                .map(|(i, (x, width))| match i {
+                    0 => format!("{x:<width$}"),
+                    _ => format!("{x:>width$}"),
+                })
+                .collect();
+
+            cells.join("  ").trim_end().to_string() + "\n"
+        })
+        .collect()
+}
+
+fn markdown(lines: &[Line], footer: &Footer) -> String {
+    let line = |row: &Row| format!("| {} |\n", row.join(" | "));
+    let mut retval = line(&HEADER.map(str::to_string)) + "| --- | ---: | :---: | ---: | ---: |\n";
+
+    for x in lines {
+        let mut row = x.row();
+        row[0] = row[0].replace('|', "\\|");
+        retval += &line(&row);
+    }
+
+    for &(label, amount) in footer {
+        retval += &line(&total_row(&format!("**{label}**"), amount));
+    }
+
+    retval
+}
+
+fn tsv(lines: &[Line], footer: &Footer) -> String {
+    let mut retval = HEADER.join("\t") + "\n";
+
+    for x in lines {
+        let duration = match x.config.r#type {
+            Type::PerTime => String::new(),
+            Type::PerHour(hm) => util::hours(hm.minutes() as u32),
+        };
+
+        retval += &format!(
+            "{}\t{}\t{duration}\t{}\t{}\n",
+            x.name.replace(['\t', '\n'], " "),
+            x.config.pay,
+            x.count,
+            x.config.sum(x.count)
+        );
+    }
+
+    for (label, amount) in footer {
+        retval += &format!("{label}\t\t\t\t{amount}\n");
+    }
+
+    retval
+}
+
+/// The configs table of the period on screen and its totals.
+pub fn summary<'a>(
+    configs: &Configs,
+    cells: impl IntoIterator<Item = &'a Cell>,
+    format: Format,
+) -> String {
+    let cells: Vec<_> = cells.into_iter().collect();
+    let lines: Vec<_> = configs
+        .iter()
+        .map(|(name, config)| Line {
+            name,
+            config,
+            count: cells.iter().filter(|x| x.contains(name)).count(),
+        })
+        .collect();
+    let footer =
+        Totals::new(lines.iter().map(|x| (x.config.sum(x.count), x.config.tax))).breakdown();
+
+    match format {
+        Format::Text => text(&lines, &footer),
+        Format::Markdown => markdown(&lines, &footer),
+        Format::Tsv => tsv(&lines, &footer),
+    }
+}
@@ -0,0 +1,153 @@
+use crate::{
+    config::{Tax, TaxBasis},
+    storage::Configs,
+};
+use std::collections::BTreeMap;
+
+/// Everything at one rate.
+pub struct RateTotal {
+    pub rate: u8,
+    pub pre_tax: u32,
+    pub tax: u32,
+}
+
+/// Consumption tax over several lines. As qualified invoices require, tax is
+/// rounded (down) once per rate rather than per line.
+pub struct Totals {
+    /// Ascending by rate.
+    pub rates: Vec<RateTotal>,
+}
+
+impl Totals {
+    /// Takes amounts at face value along with their tax.
+    pub fn new(lines: impl IntoIterator<Item = (u32, Tax)>) -> Self {
+        // (exclusive, inclusive) per rate
+        let mut buckets: BTreeMap<u8, (u64, u64)> = BTreeMap::new();
+
+        for (amount, tax) in lines {
+            let bucket = buckets.entry(tax.rate).or_default();
+
+            match tax.basis {
+                TaxBasis::Exclusive => bucket.0 += amount as u64,
+                TaxBasis::Inclusive => bucket.1 += amount as u64,
+            }
+        }
+
+        let rates = buckets
+            .into_iter()
+            .map(|(rate, (exclusive, inclusive))| {
+                let r = rate as u64;
+                let added = exclusive * r / 100;
+                let contained = inclusive * r / (100 + r);
+
+                RateTotal {
+                    rate,
+                    pre_tax: (exclusive + inclusive - contained) as u32,
+                    tax: (added + contained) as u32,
+                }
+            })
+            .collect();
+
+        Self { rates }
+    }
+
+    /// Every config with the number of shifts `count` gives for it.
+    pub fn of(configs: &Configs, count: impl Fn(&str) -> usize) -> Self {
+        Self::new(
+            configs
+                .iter()
+                .map(|(name, config)| (config.sum(count(name)), config.tax)),
+        )
+    }
+
+    pub fn pre_tax(&self) -> u32 {
+        self.rates.iter().map(|x| x.pre_tax).sum()
+    }
+
+    pub fn tax(&self) -> u32 {
+        self.rates.iter().map(|x| x.tax).sum()
+    }
+
+    pub fn total(&self) -> u32 {
+        self.pre_tax() + self.tax()
+    }
+
+    /// Labelled the same wherever totals are shown.
+    pub fn breakdown(&self) -> [(&'static str, u32); 3] {
+        [
+            ("Pre-tax", self.pre_tax()),
+            ("Consumption tax", self.tax()),
+            ("Total (tax incl.)", self.total()),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inclusive(rate: u8) -> Tax {
+        Tax {
+            basis: TaxBasis::Inclusive,
+            rate,
+        }
+    }
+
+    fn exclusive(rate: u8) -> Tax {
+        Tax {
+            basis: TaxBasis::Exclusive,
+            rate,
+        }
+    }
+
+    #[test]
+    fn inclusive_only() {
+        // per line that would be 90 + 90
+        let totals = Totals::new([(1000, inclusive(10)), (1000, inclusive(10))]);
+        assert_eq!(
+            (totals.pre_tax(), totals.tax(), totals.total()),
+            (1819, 181, 2000)
+        );
+    }
+
+    #[test]
+    fn exclusive_only() {
+        // per line that would be 100 + 100
+        let totals = Totals::new([(1005, exclusive(10)), (1005, exclusive(10))]);
+        assert_eq!(
+            (totals.pre_tax(), totals.tax(), totals.total()),
+            (2010, 201, 2211)
+        );
+    }
+
+    #[test]
+    fn mixed_bases_at_one_rate() {
+        let totals = Totals::new([(1000, exclusive(10)), (1100, inclusive(10))]);
+        assert_eq!(totals.rates.len(), 1);
+        assert_eq!(
+            (totals.pre_tax(), totals.tax(), totals.total()),
+            (2000, 200, 2200)
+        );
+    }
+
+    #[test]
+    fn several_rates() {
+        let totals = Totals::new([(999, exclusive(10)), (1000, exclusive(8))]);
+        let rates: Vec<_> = totals
+            .rates
+            .iter()
+            .map(|x| (x.rate, x.pre_tax, x.tax))
+            .collect();
+        assert_eq!(rates, [(8, 1000, 80), (10, 999, 99)]);
+        assert_eq!(totals.total(), 2178);
+    }
+
+    #[test]
+    fn zero_rate() {
+        let totals = Totals::new([(1234, inclusive(0)), (766, exclusive(0))]);
+        assert_eq!(
+            (totals.pre_tax(), totals.tax(), totals.total()),
+            (2000, 0, 2000)
+        );
+    }
+}
@@ -1,9 +1,10 @@
+use crate::{config::HourMinute, failure::Failure};
 use iced::{Background, Border, Color, Element, Font, Theme, border, font, theme, widget};
 use std::{
     collections::VecDeque,
     hash::{DefaultHasher, Hasher},
 };
-use time::{Date, Month, Weekday};
+use time::{Date, Month, Time, UtcDateTime, UtcOffset, Weekday};
 
 pub trait Some {
     fn some(self) -> Option<Self>
@@ -167,6 +168,11 @@ pub fn yen(n: u32) -> String {
     format!("{} ¥", comma_separated(n))
 }
 
+/// `125:30`
+pub fn hours(minutes: u32) -> String {
+    format!("{}:{:02}", comma_separated(minutes / 60), minutes % 60)
+}
+
 pub fn get_color(text: &str) -> Color {
     let mut hasher = DefaultHasher::new();
 
@@ -185,25 +191,125 @@ pub fn rounded_border() -> Border {
     border::rounded(2)
 }
 
-pub const fn weekday_to_column(weekday: Weekday) -> u8 {
-    match weekday {
-        Weekday::Sunday => 0,
-        Weekday::Monday => 1,
-        Weekday::Tuesday => 2,
-        Weekday::Wednesday => 3,
-        Weekday::Thursday => 4,
-        Weekday::Friday => 5,
-        Weekday::Saturday => 6,
-    }
+pub const fn weekday_to_column(weekday: Weekday, first: Weekday) -> u8 {
+    let days = WEEKDAYS.len() as u8;
+    (weekday.number_days_from_sunday() + days - first.number_days_from_sunday()) % days
+}
+
+pub fn weekdays(first: Weekday) -> [Weekday; 7] {
+    std::array::from_fn(|i| first.nth_next(i as u8))
+}
+
+pub fn current_date(offset: UtcOffset) -> Date {
+    UtcDateTime::now().to_offset(offset).date()
+}
+
+pub fn current_time(offset: UtcOffset) -> Time {
+    UtcDateTime::now().to_offset(offset).time()
+}
+
+/// Accepts `+09:00`, `-3:30`, `9` and the like.
+pub fn parse_utc_offset(input: &str) -> Option<UtcOffset> {
+    let input = input.trim();
+    let (sign, rest) = match input.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, input.strip_prefix('+').unwrap_or(input)),
+    };
+    let (hours, minutes) = rest.split_once(':').unwrap_or((rest, "0"));
+    let hours: i8 = hours.parse().ok()?;
+    let minutes: i8 = minutes.parse().ok()?;
+
+    UtcOffset::from_hms(sign * hours, sign * minutes, 0).ok()
+}
+
+/// Accepts `1:30` or just hours.
+pub fn parse_hour_minute(input: &str) -> Result<HourMinute, Failure> {
+    let input = input.trim();
+    let (hour, minute) = input.split_once(':').unwrap_or((input, "0"));
+    let parse = |x: &str| x.trim().parse().map_err(|_| Failure::DurationParse);
+
+    HourMinute::from_hm(parse(hour)?, parse(minute)?)
 }
 
-pub fn current_date() -> Option<Date> {
-    use std::time::{SystemTime, UNIX_EPOCH};
-    use time::UtcDateTime;
+/// Accepts `120:30` or just hours, without the 24-hour cap of durations.
+pub fn parse_minutes(input: &str) -> Option<u32> {
+    let input = input.trim();
+    let (hours, minutes) = input.split_once(':').unwrap_or((input, "0"));
+    let hours: u32 = hours.trim().parse().ok()?;
+    let minutes: u32 = minutes.trim().parse().ok().filter(|&x| x < 60)?;
 
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
+    hours.checked_mul(60)?.checked_add(minutes)
+}
+
+/// Ignores thousands separators and a yen sign.
+pub fn parse_amount(input: &str) -> Option<u32> {
+    input
+        .chars()
+        .filter(|c| !matches!(c, ',' | '¥' | ' '))
+        .collect::<String>()
+        .parse()
         .ok()
-        .and_then(|timestamp| UtcDateTime::from_unix_timestamp(timestamp.as_secs() as i64).ok())
-        .map(|x| x.date())
+}
+
+/// Days past the end of the picked month are clamped to its last day.
+pub fn date_picker<'a, Message: Clone + 'a>(
+    date: Date,
+    on_pick: impl Fn(Date) -> Message + Clone + 'a,
+) -> widget::Row<'a, Message> {
+    use widget::{pick_list, row};
+
+    let pick = move |year: i32, month: Month, day: u8| {
+        let day = day.min(month.length(year));
+        on_pick(Date::from_calendar_date(year, month, day).unwrap_or(date))
+    };
+
+    let years: Vec<i32> = (date.year() - 10..=date.year() + 10).collect();
+    let days: Vec<u8> = (1..=date.month().length(date.year())).collect();
+
+    row![
+        pick_list(years, Some(date.year()), {
+            let pick = pick.clone();
+            move |year| pick(year, date.month(), date.day())
+        }),
+        pick_list(MONTHS, Some(date.month()), {
+            let pick = pick.clone();
+            move |month| pick(date.year(), month, date.day())
+        }),
+        pick_list(days, Some(date.day()), move |day| {
+            pick(date.year(), date.month(), day)
+        }),
+    ]
+    .spacing(6)
+}
+
+pub fn danger_container<'a, Message>(
+    content: impl Into<Element<'a, Message>>,
+) -> widget::Container<'a, Message> {
+    use widget::container;
+
+    container(content).style(|theme: &Theme| {
+        let pair = theme.extended_palette().danger.weak;
+
+        container::Style {
+            background: Background::Color(pair.color).some(),
+            text_color: pair.text.some(),
+            border: rounded_border(),
+            ..Default::default()
+        }
+    })
+}
+
+pub fn outlined_container<'a, Message>(
+    content: impl Into<Element<'a, Message>>,
+) -> widget::Container<'a, Message> {
+    use widget::container;
+
+    container(content).style(|theme: &Theme| container::Style {
+        border: Border {
+            color: theme.extended_palette().primary.strong.color,
+            width: 2.0,
+            ..rounded_border()
+        },
+        ..Default::default()
+    })
 }
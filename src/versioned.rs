@@ -0,0 +1,48 @@
+use crate::failure::Cause;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::Value;
+
+/// JSON written with a version number, so that files from older versions can
+/// be upgraded step by step when read.
+pub trait Versioned: Serialize + DeserializeOwned {
+    /// Bump together with a new arm in [`Versioned::migrate`].
+    const VERSION: u64;
+
+    /// Upgrades `value` by exactly one version.
+    fn migrate(value: Value, from: u64) -> Result<Value, Cause>;
+}
+
+#[derive(Serialize, Deserialize)]
+struct Envelope<T> {
+    version: u64,
+    #[serde(flatten)]
+    data: T,
+}
+
+/// Files written before versioning have no number and count as version 0. A
+/// save's config may itself be named "version", but its value is then an
+/// object rather than a number.
+fn version(value: &Value) -> u64 {
+    value.get("version").and_then(Value::as_u64).unwrap_or(0)
+}
+
+pub fn from_str<T: Versioned>(read: &str) -> Result<T, Cause> {
+    let mut value: Value = serde_json::from_str(read)?;
+
+    loop {
+        match version(&value) {
+            version if version == T::VERSION => break,
+            version if version < T::VERSION => value = T::migrate(value, version)?,
+            version => return Err(Cause::Version(version)),
+        }
+    }
+
+    Ok(serde_json::from_value::<Envelope<T>>(value)?.data)
+}
+
+pub fn to_string<T: Versioned>(data: &T) -> Result<String, Cause> {
+    Ok(serde_json::to_string(&Envelope {
+        version: T::VERSION,
+        data,
+    })?)
+}
@@ -0,0 +1,196 @@
+use crate::{
+    config::{Tax, Type},
+    save_data::PeriodRecord,
+    storage::Configs,
+    tax::Totals,
+    util,
+};
+use iced::{Color, Point, Rectangle, Renderer, Size, Theme, alignment, mouse, widget::canvas};
+use std::collections::{BTreeMap, BTreeSet};
+use time::{Date, Month};
+
+pub struct ConfigTotal {
+    pub name: String,
+    pub count: usize,
+    /// At face value.
+    pub sum: u32,
+    pub tax: Tax,
+}
+
+impl ConfigTotal {
+    pub fn with_tax(&self) -> u32 {
+        Totals::new([(self.sum, self.tax)]).total()
+    }
+}
+
+/// One monthly pay period of the year overview.
+pub struct PeriodTotal {
+    pub month: Month,
+    pub start: Date,
+    /// Exclusive.
+    pub end: Date,
+    /// Per-time configs have no duration and are left out.
+    pub minutes: u32,
+    pub configs: Vec<ConfigTotal>,
+}
+
+impl PeriodTotal {
+    /// Tax included, rounded once over the period.
+    pub fn sum(&self) -> u32 {
+        Totals::new(self.configs.iter().map(|x| (x.sum, x.tax))).total()
+    }
+
+    /// `Jan 25 - Feb 24`
+    pub fn label(&self) -> String {
+        let last = self.end.previous_day().unwrap_or(self.end);
+
+        format!(
+            "{} {} - {} {}",
+            util::short_month(self.start.month()),
+            self.start.day(),
+            util::short_month(last.month()),
+            last.day()
+        )
+    }
+}
+
+/// Adds up the records over each `(month, start, end)`, planned shifts
+/// included. A date that shows up in several records (say, a monthly and a
+/// custom period) counts once.
+pub fn totals(
+    bounds: impl IntoIterator<Item = (Month, Date, Date)>,
+    configs: &Configs,
+    periods: &[PeriodRecord],
+) -> Vec<PeriodTotal> {
+    let mut assignments: BTreeMap<Date, BTreeSet<&str>> = BTreeMap::new();
+
+    for (date, names) in periods.iter().flat_map(|x| &x.assignments) {
+        assignments
+            .entry(*date)
+            .or_default()
+            .extend(names.keys().map(String::as_str));
+    }
+
+    bounds
+        .into_iter()
+        .map(|(month, start, end)| {
+            let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+
+            for names in assignments.range(start..end).map(|(_, names)| names) {
+                for name in names {
+                    *counts.entry(name).or_default() += 1;
+                }
+            }
+
+            let mut minutes = 0;
+            let configs = counts
+                .into_iter()
+                .filter_map(|(name, count)| {
+                    let config = configs.get(name)?;
+
+                    if let Type::PerHour(hm) = config.r#type {
+                        minutes += hm.minutes() as u32 * count as u32;
+                    }
+
+                    Some(ConfigTotal {
+                        name: name.to_string(),
+                        count,
+                        sum: config.sum(count),
+                        tax: config.tax,
+                    })
+                })
+                .collect();
+
+            PeriodTotal {
+                month,
+                start,
+                end,
+                minutes,
+                configs,
+            }
+        })
+        .collect()
+}
+
+/// Monthly totals as bars stacked by config.
+pub struct Chart {
+    pub totals: Vec<PeriodTotal>,
+}
+
+impl Chart {
+    const LABEL_HEIGHT: f32 = 20.0;
+    const BAR_GAP: f32 = 8.0;
+}
+
+impl<Message> canvas::Program<Message> for Chart {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<canvas::Geometry> {
+        let mut frame = canvas::Frame::new(renderer, bounds.size());
+        let text_color = theme.palette().text;
+
+        let max = self.totals.iter().map(PeriodTotal::sum).max().unwrap_or(0);
+        let slot = bounds.width / self.totals.len().max(1) as f32;
+        let plot_height = (bounds.height - 2.0 * Self::LABEL_HEIGHT).max(0.0);
+
+        for (i, total) in self.totals.iter().enumerate() {
+            let x = i as f32 * slot + Self::BAR_GAP / 2.0;
+            let width = (slot - Self::BAR_GAP).max(1.0);
+            let mut y = Self::LABEL_HEIGHT + plot_height;
+
+            if max > 0 {
+                for config in &total.configs {
+                    let height = plot_height * config.with_tax() as f32 / max as f32;
+
+                    y -= height;
+                    frame.fill_rectangle(
+                        Point::new(x, y),
+                        Size::new(width, height),
+                        util::get_color(&config.name),
+                    );
+                }
+            }
+
+            let center = x + width / 2.0;
+
+            if total.sum() > 0 {
+                frame.fill_text(canvas::Text {
+                    content: format!("{}k", total.sum() / 1000),
+                    position: Point::new(center, y - 2.0),
+                    color: text_color,
+                    size: 12.into(),
+                    horizontal_alignment: alignment::Horizontal::Center,
+                    vertical_alignment: alignment::Vertical::Bottom,
+                    ..Default::default()
+                });
+            }
+
+            frame.fill_text(canvas::Text {
+                content: util::short_month(total.month).to_string(),
+                position: Point::new(center, bounds.height - Self::LABEL_HEIGHT / 2.0),
+                color: text_color,
+                horizontal_alignment: alignment::Horizontal::Center,
+                vertical_alignment: alignment::Vertical::Center,
+                ..Default::default()
+            });
+        }
+
+        frame.fill_rectangle(
+            Point::new(0.0, Self::LABEL_HEIGHT + plot_height),
+            Size::new(bounds.width, 1.0),
+            Color {
+                a: 0.5,
+                ..text_color
+            },
+        );
+
+        vec![frame.into_geometry()]
+    }
+}